@@ -0,0 +1,172 @@
+//! Análise estruturada: a resposta final pedida como JSON (pelo `format` do Ollama) e
+//! lida num [`Analysis`], que dá pra agregar e filtrar.
+//!
+//! Quando a resposta não segue o esquema, o relatório fica com o texto livre como antes.
+
+use crate::i18n::Messages;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+
+/// Nível de risco de uma alteração.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+impl RiskLevel {
+    /// Nome do nível no idioma do catálogo `m`.
+    pub fn label<'m>(&self, m: &'m Messages) -> &'m str {
+        match self {
+            RiskLevel::Low => m.risk_low,
+            RiskLevel::Medium => m.risk_medium,
+            RiskLevel::High => m.risk_high,
+        }
+    }
+}
+
+/// Tipo de alteração; um commit pode ter mais de um.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Category {
+    Refactor,
+    Bugfix,
+    Feature,
+    Dependency,
+    Config,
+    Schema,
+    TestOnly,
+}
+
+impl Category {
+    pub const ALL: [Category; 7] = [
+        Category::Refactor,
+        Category::Bugfix,
+        Category::Feature,
+        Category::Dependency,
+        Category::Config,
+        Category::Schema,
+        Category::TestOnly,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Refactor => "refactor",
+            Category::Bugfix => "bugfix",
+            Category::Feature => "feature",
+            Category::Dependency => "dependency",
+            Category::Config => "config",
+            Category::Schema => "schema",
+            Category::TestOnly => "test-only",
+        }
+    }
+}
+
+/// Campos de [`Analysis`] na ordem em que o prompt os pede.
+pub const FIELDS: [&str; 7] = ["purpose", "impact", "observations", "risk_level", "categories", "components", "breaking_change"];
+
+/// Resposta final no formato de [`schema`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Analysis {
+    pub purpose: String,
+    pub impact: String,
+    pub observations: String,
+    pub risk_level: RiskLevel,
+    pub categories: Vec<Category>,
+    /// Módulos, pacotes ou camadas afetados.
+    pub components: Vec<String>,
+    pub breaking_change: bool,
+}
+
+/// Esquema JSON mandado no `format` do Ollama.
+pub fn schema() -> &'static Value {
+    static SCHEMA: OnceLock<Value> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        let categories: Vec<&str> = Category::ALL.iter().map(Category::name).collect();
+        json!({
+            "type": "object",
+            "properties": {
+                "purpose": { "type": "string" },
+                "impact": { "type": "string" },
+                "observations": { "type": "string" },
+                "risk_level": { "type": "string", "enum": ["low", "medium", "high"] },
+                "categories": { "type": "array", "items": { "type": "string", "enum": categories } },
+                "components": { "type": "array", "items": { "type": "string" } },
+                "breaking_change": { "type": "boolean" }
+            },
+            "required": FIELDS
+        })
+    })
+}
+
+impl Analysis {
+    /// Lê a resposta do modelo; o erro diz por que ela não serve.
+    pub fn parse(response: &str, m: &Messages) -> Result<Analysis, String> {
+        // alguns modelos cercam o JSON com ``` ou texto mesmo com o format
+        let start = response.find('{').ok_or(m.json_missing)?;
+        let end = response.rfind('}').filter(|&end| end > start).ok_or(m.json_missing)?;
+        let analysis: Analysis = serde_json::from_str(&response[start..=end]).map_err(|e| e.to_string())?;
+
+        if analysis.purpose.trim().is_empty() {
+            return Err(m.json_empty_purpose.to_string());
+        }
+        Ok(analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Locale;
+
+    const VALID: &str = r#"{
+        "purpose": "corrige o cache",
+        "impact": "menos chamadas",
+        "observations": "nenhuma",
+        "risk_level": "medium",
+        "categories": ["bugfix", "test-only"],
+        "components": ["cache"],
+        "breaking_change": false
+    }"#;
+
+    #[test]
+    fn parses_valid_json() {
+        let analysis = Analysis::parse(VALID, Locale::PtBr.messages()).unwrap();
+        assert_eq!(analysis.purpose, "corrige o cache");
+        assert_eq!(analysis.risk_level, RiskLevel::Medium);
+        assert_eq!(analysis.categories, [Category::Bugfix, Category::TestOnly]);
+        assert_eq!(analysis.components, ["cache"]);
+        assert!(!analysis.breaking_change);
+    }
+
+    #[test]
+    fn ignores_fences_and_text_around_the_json() {
+        let m = Locale::PtBr.messages();
+        for response in [format!("```json\n{VALID}\n```"), format!("Aqui está a análise:\n{VALID}\nEspero ter ajudado.")] {
+            assert_eq!(Analysis::parse(&response, m), Analysis::parse(VALID, m), "{response}");
+        }
+    }
+
+    #[test]
+    fn rejects_what_does_not_fit_the_schema() {
+        let m = Locale::PtBr.messages();
+        for response in ["sem json aqui", "} antes de {", ""] {
+            assert_eq!(Analysis::parse(response, m).unwrap_err(), m.json_missing, "{response}");
+        }
+
+        let empty_purpose = VALID.replace("corrige o cache", "  ");
+        assert_eq!(Analysis::parse(&empty_purpose, m).unwrap_err(), m.json_empty_purpose);
+
+        let invalid_enum = VALID.replace("\"medium\"", "\"extreme\"");
+        assert!(Analysis::parse(&invalid_enum, m).unwrap_err().contains("extreme"));
+
+        let unknown_category = VALID.replace("\"bugfix\"", "\"docs\"");
+        assert!(Analysis::parse(&unknown_category, m).unwrap_err().contains("docs"));
+
+        let missing_field = VALID.replace("\"breaking_change\": false", "\"extra\": 1");
+        assert!(Analysis::parse(&missing_field, m).unwrap_err().contains("breaking_change"));
+    }
+}
@@ -0,0 +1,900 @@
+use crate::analysis::{self, Analysis, RiskLevel};
+use crate::chunk::{group_related_files, split_diff_into_chunks, DiffChunk};
+use crate::config::Settings;
+use crate::diff::{FileChange, FileDiff, parse_unified_diff};
+use crate::filter::{ExcludedFile, PathFilter};
+use crate::git::{self, CommitInfo, CommitQuery, PendingChanges};
+use crate::i18n::{Locale, Messages};
+use crate::llm::{call_llm, clean_response, context_window, create_backend, CancelToken, Cancelled, LlmBackend};
+use crate::markdown::{render_file_with_note, render_files};
+use crate::profile::Profile;
+use crate::prompt::*;
+use crate::report;
+use crate::risk::CommitRisk;
+use crate::term::*;
+use crate::tokens::{estimate_tokens, TokenBudget};
+use std::fmt::Write as FmtWrite;
+use std::io::Write as IoWrite;
+use std::sync::mpsc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+// abaixo disso os pedacos ficariam pequenos demais pra dizer alguma coisa
+const MIN_CONTENT_TOKENS: usize = 256;
+
+// cabecalho "**Parte N:**" e separadores de cada analise num grupo
+const GROUP_ITEM_TOKENS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnalysisKind {
+    Commit,
+    Pending,
+}
+
+/// Resultado da análise de um commit (ou de um diff sintético, como o staged).
+#[derive(Debug, Clone)]
+pub struct AnalysisReport {
+    pub commit: CommitInfo,
+    /// Resposta lida do JSON; `None` fora do modo `structured` ou quando ela não seguiu o esquema.
+    pub structured: Option<Analysis>,
+    /// Perfil usado, que define o formato do relatório.
+    pub profile: Profile,
+    /// Idioma do relatório.
+    pub locale: Locale,
+    pub analysis: String,
+    pub formatted_diff: String,
+    /// `false` quando algum pedaço falhou, a resposta veio cortada ou parte do diff ficou
+    /// de fora pelo limite de pedaços.
+    pub complete: bool,
+    /// `None` quando o diff coube numa requisição só.
+    pub reduction: Option<Reduction>,
+    /// Arquivos analisados, com o tipo de mudança de cada um.
+    pub files: Vec<FileChange>,
+    /// Arquivos do diff que os filtros de caminho deixaram fora do prompt.
+    pub excluded: Vec<ExcludedFile>,
+}
+
+/// Como a análise de um diff dividido em pedaços foi montada.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reduction {
+    pub chunks: usize,
+    /// Níveis de resumo até a análise final; 1 quando as análises dos pedaços
+    /// couberam direto no resumo consolidado.
+    pub depth: usize,
+    /// Pedaços são arquivos (ou grupos de arquivos) do modo por arquivo.
+    pub per_file: bool,
+}
+
+impl AnalysisReport {
+    pub fn to_markdown(&self) -> String {
+        report::generate_final_document(self)
+    }
+
+    pub fn filename(&self) -> String {
+        report::generate_filename(&self.commit.short_hash, &self.commit.message, self.locale.messages())
+    }
+
+    pub fn risk_level(&self) -> Option<RiskLevel> {
+        match &self.structured {
+            Some(structured) => Some(structured.risk_level),
+            None => extract_risk_level(&self.analysis),
+        }
+    }
+
+    /// Pontuação e categorias para ordenar o commit entre os outros do intervalo.
+    pub fn risk(&self) -> CommitRisk {
+        CommitRisk::assess(&self.commit, &self.analysis, self.structured.as_ref())
+    }
+}
+
+/// Análise do diff de uma branch desde o merge-base, com os commits que a compõem.
+#[derive(Debug, Clone)]
+pub struct BranchReport {
+    pub base: String,
+    pub head: String,
+    pub merge_base: String,
+    pub commits: Vec<CommitInfo>,
+    pub report: AnalysisReport,
+}
+
+impl BranchReport {
+    pub fn to_markdown(&self) -> String {
+        report::generate_pr_document(
+            &self.base,
+            &self.head,
+            &self.merge_base,
+            &self.commits,
+            &self.report,
+        )
+    }
+
+    pub fn filename(&self) -> String {
+        report::generate_pr_filename(&self.base, &self.head)
+    }
+}
+
+/// Pipeline completo: extrai do git, formata, divide, monta os prompts e chama o modelo.
+///
+/// Os métodos bloqueiam a thread até o modelo responder, com um runtime do tokio próprio.
+/// Dentro de um runtime de várias threads (como o do `#[tokio::main]`) podem ser chamados
+/// direto; num runtime `current_thread` as chamadas ao modelo devolvem erro, então rode-os
+/// numa thread fora dele (`std::thread::spawn`).
+pub struct Analyzer {
+    settings: Settings,
+    /// Catálogo do `locale` das configurações: terminal, prompts e relatórios.
+    messages: &'static Messages,
+    backend: Box<dyn LlmBackend>,
+    filter: PathFilter,
+    prompts: PromptTemplates,
+    cancel: CancelToken,
+}
+
+impl Analyzer {
+    pub fn new(settings: Settings) -> Result<Self, Box<dyn std::error::Error>> {
+        settings.validate()?;
+
+        let backend = create_backend(&settings)?;
+        let filter = PathFilter::new(&settings)?;
+        let prompts = PromptTemplates::load(&settings)?;
+
+        Ok(Analyzer { messages: settings.locale.messages(), settings, backend, filter, prompts, cancel: CancelToken::default() })
+    }
+
+    /// Usa um backend próprio (útil para testes ou servidores não suportados).
+    pub fn with_backend(settings: Settings, backend: Box<dyn LlmBackend>) -> Result<Self, Box<dyn std::error::Error>> {
+        settings.validate()?;
+        let filter = PathFilter::new(&settings)?;
+        let prompts = PromptTemplates::load(&settings)?;
+        Ok(Analyzer { messages: settings.locale.messages(), settings, backend, filter, prompts, cancel: CancelToken::default() })
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Token que interrompe as requisições em andamento deste analyzer.
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    /// Analisa um commit já existente, dado por qualquer revisão (`HEAD`, hash abreviado,
+    /// branch...). `None` quando não há alterações de código.
+    pub fn analyze_commit(&self, rev: &str) -> Result<Option<AnalysisReport>, Box<dyn std::error::Error>> {
+        let hash = git::resolve_commit(&self.settings.repo_path, rev)?;
+        let (commit_info, raw_diff) = git::get_commit(&hash, &self.settings.repo_path)?;
+
+        print_commit_header(&commit_info, self.messages);
+        self.analyze_diff(commit_info, &raw_diff, AnalysisKind::Commit)
+    }
+
+    /// Analisa alterações ainda não commitadas, pedindo também o nível de risco.
+    pub fn analyze_pending(
+        &self,
+        pending: PendingChanges,
+        message: &str,
+    ) -> Result<Option<AnalysisReport>, Box<dyn std::error::Error>> {
+        let raw_diff = git::get_pending_diff(&self.settings.repo_path, pending)?;
+        // sem alteracoes nao tem cabecalho, so o aviso de quem chama
+        if raw_diff.trim().is_empty() {
+            return Ok(None);
+        }
+        let commit_info = git::get_pending_info(&self.settings.repo_path, pending, &raw_diff, message, self.messages)?;
+
+        print_commit_header(&commit_info, self.messages);
+        self.analyze_diff(commit_info, &raw_diff, AnalysisKind::Pending)
+    }
+
+    /// Analisa `head` desde o merge-base com `base` como uma única alteração.
+    pub fn analyze_branch(&self, base: &str, head: &str) -> Result<Option<BranchReport>, Box<dyn std::error::Error>> {
+        let repo_path = &self.settings.repo_path;
+        let base_hash = git::resolve_commit(repo_path, base)?;
+        let head_hash = git::resolve_commit(repo_path, head)?;
+        let merge_base = git::merge_base(repo_path, &base_hash, &head_hash)?;
+
+        let query = CommitQuery {
+            range: Some(format!("{}..{}", merge_base, head_hash)),
+            ..Default::default()
+        };
+        let mut commits = git::list_commits(repo_path, &query)?
+            .iter()
+            .map(|hash| git::get_commit_info(hash, repo_path))
+            .collect::<Result<Vec<_>, _>>()?;
+        // apendice em ordem cronologica
+        commits.reverse();
+
+        if commits.is_empty() {
+            return Ok(None);
+        }
+
+        let m = self.messages;
+        println!("{}{}{} {}", COLOR_GRAY, m.merge_base, COLOR_RESET, git::short_hash(&merge_base));
+        println!("{}{}{} {}", COLOR_GRAY, m.commits, COLOR_RESET, commits.len());
+
+        // o modelo recebe os assuntos dos commits como "mensagem"
+        let mut message = (m.branch_message)(&head, &base, &commits.len());
+        for commit in &commits {
+            write!(message, "\n- {}", commit.message).unwrap();
+        }
+
+        let raw_diff = git::get_range_diff(repo_path, &merge_base, &head_hash)?;
+        let range_info = git::get_range_info(repo_path, &head_hash, &raw_diff, &message)?;
+        print_commit_header(&range_info, self.messages);
+
+        let Some(report) = self.analyze_diff(range_info, &raw_diff, AnalysisKind::Commit)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(BranchReport {
+            base: base.to_string(),
+            head: head.to_string(),
+            merge_base,
+            commits,
+            report,
+        }))
+    }
+
+    // formata, divide se precisar e manda pro modelo; None quando nao ha mudanca
+    pub fn analyze_diff(
+        &self,
+        commit_info: CommitInfo,
+        raw_diff: &str,
+        kind: AnalysisKind,
+    ) -> Result<Option<AnalysisReport>, Box<dyn std::error::Error>> {
+        // checa se tem mudanca
+        let (files, excluded) = self.filter.partition(parse_unified_diff(raw_diff));
+        report_excluded(&excluded, self.messages);
+        if files.is_empty() {
+            return Ok(None);
+        }
+
+        let mut formatted_diff = render_files(&files, self.messages);
+        let diff_tokens = estimate_tokens(&formatted_diff);
+        let diff_limit = self.content_limit(&self.prompts.build_analysis_prompt(kind, &commit_info, ""), self.settings.max_diff_size)?;
+
+        let m = self.messages;
+        println!("{}{}{} {}", COLOR_GRAY, m.diff_size, COLOR_RESET, (m.diff_size_tokens)(&diff_tokens, &diff_limit));
+
+        // processa o diff grande ou normal
+        let (analysis, complete, reduction) = if self.settings.per_file {
+            let (analysis, complete, reduction, annotated) = self.process_per_file(&commit_info, &files, kind)?;
+            formatted_diff = annotated;
+            (analysis, complete, Some(reduction))
+        } else if diff_tokens > diff_limit {
+            println!("{}[{}]{} {}", COLOR_YELLOW, m.label_chunk, COLOR_RESET, m.diff_too_large);
+            let (analysis, complete, reduction) = self.process_large_diff(&commit_info, &files, kind, self.final_format())?;
+            (analysis, complete, Some(reduction))
+        } else {
+            let analysis_prompt = self.prompts.build_analysis_prompt(kind, &commit_info, &formatted_diff);
+            let analysis = call_llm(self.backend.as_ref(), &self.settings, &self.cancel, &analysis_prompt, self.final_format())?;
+            let complete = !analysis.contains(m.partial_marker);
+            (analysis, complete, None)
+        };
+
+        let analysis = clean_response(analysis);
+        let structured = self.parse_structured(&analysis);
+        let analysis = structured.as_ref().map_or(analysis, |structured| report::render_analysis(structured, m));
+
+        Ok(Some(AnalysisReport {
+            commit: commit_info,
+            structured,
+            profile: self.settings.profile,
+            locale: self.settings.locale,
+            analysis,
+            formatted_diff,
+            complete,
+            reduction,
+            files: files.iter().map(|file| file.change(m)).collect(),
+            excluded,
+        }))
+    }
+
+    /// Monta o prompt `template` sem chamar o modelo, para a revisão `rev` ou, com `None`,
+    /// para as alterações staged. O diff vai inteiro, mesmo que a análise o dividisse em
+    /// pedaços, e as análises que viriam do modelo aparecem como marcadores.
+    pub fn render_prompt(&self, rev: Option<&str>, template: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let repo_path = &self.settings.repo_path;
+        let (commit_info, raw_diff, kind) = match rev {
+            Some(rev) => {
+                let (commit, diff) = git::get_commit(&git::resolve_commit(repo_path, rev)?, repo_path)?;
+                (commit, diff, AnalysisKind::Commit)
+            }
+            None => {
+                let pending = PendingChanges::Staged;
+                let raw_diff = git::get_pending_diff(repo_path, pending)?;
+                (git::get_pending_info(repo_path, pending, &raw_diff, "", self.messages)?, raw_diff, AnalysisKind::Pending)
+            }
+        };
+
+        let (files, _) = self.filter.partition(parse_unified_diff(&raw_diff));
+        let diff = render_files(&files, self.messages);
+        let paths: Vec<String> = files.iter().map(|f| f.path().to_string()).collect();
+        let analyses: Vec<String> = (1..=2).map(|i| (self.messages.placeholder_analysis)(&i)).collect();
+
+        let prompts = &self.prompts;
+        Ok(match template {
+            "analysis" => prompts.build_analysis_prompt(kind, &commit_info, &diff),
+            "chunk" => prompts.build_chunk_analysis_prompt(&commit_info, &diff, 1, 1),
+            "summary" => prompts.build_summary_prompt(kind, &commit_info, &analyses, self.settings.structured),
+            "file_analysis" => prompts.build_file_analysis_prompt(&commit_info, &paths, &diff),
+            "file_synthesis" => prompts.build_file_synthesis_prompt(kind, &commit_info, &analyses, self.settings.structured),
+            "group_summary" => prompts.build_group_summary_prompt(&commit_info, &analyses, 1, 1),
+            "commit_message" => prompts.build_commit_message_prompt(&commit_info, &diff, false),
+            _ => return Err((self.messages.unknown_template)(&template, &TEMPLATE_NAMES.join(", ")).into()),
+        })
+    }
+
+    // usa o diff staged direto, ou o resumo dos pedacos quando o diff e grande
+    pub fn commit_message(&self, conventional: bool) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let pending = PendingChanges::Staged;
+        let raw_diff = git::get_pending_diff(&self.settings.repo_path, pending)?;
+        let commit_info = git::get_pending_info(&self.settings.repo_path, pending, &raw_diff, "", self.messages)?;
+
+        let (files, excluded) = self.filter.partition(parse_unified_diff(&raw_diff));
+        report_excluded(&excluded, self.messages);
+        if files.is_empty() {
+            return Ok(None);
+        }
+
+        let formatted_diff = render_files(&files, self.messages);
+        let limit = self.content_limit(&self.prompts.build_commit_message_prompt(&commit_info, "", conventional), self.settings.max_diff_size)?;
+        let content = if estimate_tokens(&formatted_diff) > limit {
+            println!("{}[{}]{} {}", COLOR_YELLOW, self.messages.label_chunk, COLOR_RESET, self.messages.diff_too_large_message);
+            self.process_large_diff(&commit_info, &files, AnalysisKind::Pending, None)?.0
+        } else {
+            formatted_diff
+        };
+
+        let prompt = self.prompts.build_commit_message_prompt(&commit_info, &content, conventional);
+        let response = call_llm(self.backend.as_ref(), &self.settings, &self.cancel, &prompt, None)?;
+
+        let message = clean_commit_message(&clean_response(response));
+        if message.is_empty() {
+            return Err(self.messages.empty_commit_message.into());
+        }
+        Ok(Some(message))
+    }
+
+    // quanto conteudo cabe num prompt: o que sobra da janela, ou o limite configurado se for menor
+    fn content_limit(&self, empty_prompt: &str, configured: Option<usize>) -> Result<usize, Box<dyn std::error::Error>> {
+        let budget = self.token_budget()?;
+        let available = budget.available_for(empty_prompt);
+        if available < MIN_CONTENT_TOKENS {
+            return Err((self.messages.context_too_small)(&budget.context_window, &budget.answer_tokens).into());
+        }
+        Ok(configured.map_or(available, |limit| limit.min(available)))
+    }
+
+    // so a resposta final vem em JSON; os pedacos e resumos intermediarios continuam em texto
+    fn final_format(&self) -> Option<&'static serde_json::Value> {
+        self.settings.structured.then(analysis::schema)
+    }
+
+    // resposta fora do esquema (ou cortada) fica como texto livre, como sem o modo estruturado
+    fn parse_structured(&self, response: &str) -> Option<Analysis> {
+        if !self.settings.structured {
+            return None;
+        }
+        Analysis::parse(response, self.messages)
+            .inspect_err(|e| println!("{}[{}]{} {}", COLOR_YELLOW, LABEL_JSON, COLOR_RESET, (self.messages.structured_fallback)(e)))
+            .ok()
+    }
+
+    fn token_budget(&self) -> Result<TokenBudget, Box<dyn std::error::Error>> {
+        Ok(TokenBudget {
+            context_window: context_window(self.backend.as_ref(), self.messages)?,
+            answer_tokens: self.settings.answer_tokens,
+        })
+    }
+
+    // devolve a analise final, se todas as respostas vieram completas e como foi a reducao
+    // `format` vai so no prompt final, e so quando ele e a resposta do relatorio
+    fn process_large_diff(
+        &self,
+        commit_info: &CommitInfo,
+        files: &[FileDiff],
+        kind: AnalysisKind,
+        format: Option<&serde_json::Value>,
+    ) -> Result<(String, bool, Reduction), Box<dyn std::error::Error>> {
+        let settings = &self.settings;
+        let m = self.messages;
+        let empty_prompt = self.prompts.build_chunk_analysis_prompt(commit_info, "", settings.max_chunks, settings.max_chunks);
+        let mut chunks = split_diff_into_chunks(files, self.content_limit(&empty_prompt, settings.chunk_size)?, m);
+        if chunks.is_empty() {
+            return Err(m.no_chunks.into());
+        }
+        // o que passa do limite fica fora da analise, que entao nao conta como completa
+        let truncated = chunks.len() > settings.max_chunks;
+        if truncated {
+            println!("{}[{}]{} {}", COLOR_YELLOW, m.label_chunk, COLOR_RESET, (m.too_many_chunks)(&chunks.len(), &settings.max_chunks));
+            chunks.truncate(settings.max_chunks);
+        }
+
+        println!("{}[{}]{} {}", COLOR_YELLOW, m.label_chunk, COLOR_RESET, (m.split_into)(&chunks.len()));
+
+        let total = chunks.len();
+        let prompts: Vec<String> = chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| self.prompts.build_chunk_analysis_prompt(commit_info, &chunk.content, i + 1, total))
+            .collect();
+        let describe = |i: usize| (m.chunk_progress)(&(i + 1), &total, &chunks[i].size);
+        let (analyses, complete) = self.call_llm_parallel(&prompts, describe, m.item_chunk)?;
+
+        let structured = format.is_some();
+        let summary_prompt = |analyses: &[String]| self.prompts.build_summary_prompt(kind, commit_info, analyses, structured);
+        let (analysis, reduced_complete, depth) = self.reduce(commit_info, analyses, summary_prompt, format)?;
+        Ok((analysis, complete && reduced_complete && !truncated, Reduction { chunks: total, depth, per_file: false }))
+    }
+
+    // uma analise por arquivo (ou grupo de arquivos pequenos da mesma pasta) e uma sintese no fim;
+    // devolve tambem o diff com cada analise embaixo do seu arquivo
+    fn process_per_file(&self, commit_info: &CommitInfo, files: &[FileDiff], kind: AnalysisKind) -> Result<(String, bool, Reduction, String), Box<dyn std::error::Error>> {
+        let settings = &self.settings;
+        let m = self.messages;
+        let limit = self.content_limit(&self.prompts.build_file_analysis_prompt(commit_info, &[], ""), settings.max_diff_size)?;
+        let mut groups = group_related_files(files, limit, m);
+        // arquivos alem do limite ficam como nao analisados, e a analise como incompleta
+        let truncated = groups.len() > settings.max_chunks;
+        if truncated {
+            println!("{}[{}]{} {}", COLOR_YELLOW, m.label_chunk, COLOR_RESET, (m.too_many_groups)(&groups.len(), &settings.max_chunks));
+            groups.truncate(settings.max_chunks);
+        }
+        println!("{}[{}]{} {}", COLOR_YELLOW, m.label_chunk, COLOR_RESET, (m.files_in_analyses)(&files.len(), &groups.len()));
+
+        // os que cabem vao em paralelo; um arquivo maior que a janela passa pela divisao em pedacos
+        let (fitting, oversized): (Vec<usize>, Vec<usize>) = (0..groups.len()).partition(|&i| groups[i].size <= limit);
+        let prompts: Vec<String> = fitting
+            .iter()
+            .map(|&i| self.prompts.build_file_analysis_prompt(commit_info, &groups[i].files, &groups[i].content))
+            .collect();
+        let describe = |n: usize| {
+            let group = &groups[fitting[n]];
+            (m.file_progress)(&(fitting[n] + 1), &groups.len(), &group.files.join(", "), &group.size)
+        };
+        let (responses, mut complete) = self.call_llm_parallel(&prompts, describe, m.item_file)?;
+        complete &= !truncated;
+
+        let mut analyses: Vec<Option<String>> = vec![None; groups.len()];
+        for (i, response) in fitting.into_iter().zip(responses) {
+            analyses[i] = Some(response);
+        }
+        for i in oversized {
+            let path = &groups[i].files[0];
+            let file = files.iter().find(|f| f.path() == path).expect("arquivo do grupo");
+            println!("{}[{}]{} {}", COLOR_YELLOW, m.label_chunk, COLOR_RESET, (m.file_too_large)(path));
+            // analise de um arquivo so, que ainda entra na sintese: texto livre
+            let (analysis, file_complete, _) = self.process_large_diff(commit_info, std::slice::from_ref(file), kind, None)?;
+            complete &= file_complete;
+            analyses[i] = Some(analysis);
+        }
+        let analyses: Vec<String> = analyses.into_iter().flatten().collect();
+
+        let items: Vec<String> = groups
+            .iter()
+            .zip(&analyses)
+            .map(|(group, analysis)| format!("**{}**\n{}", code_list(&group.files), analysis))
+            .collect();
+        let format = self.final_format();
+        let structured = format.is_some();
+        let synthesis_prompt = |items: &[String]| self.prompts.build_file_synthesis_prompt(kind, commit_info, items, structured);
+        let (synthesis, reduced_complete, depth) = self.reduce(commit_info, items, synthesis_prompt, format)?;
+
+        let annotated = render_files_with_analyses(files, &groups, &analyses, m);
+        let reduction = Reduction { chunks: groups.len(), depth, per_file: true };
+        Ok((synthesis, complete && reduced_complete, reduction, annotated))
+    }
+
+    // enquanto as analises nao cabem num prompt so, resume em grupos e sobe um nivel; devolve a
+    // resposta do prompt final, se tudo veio completo e quantos niveis de resumo houve
+    fn reduce(
+        &self,
+        commit_info: &CommitInfo,
+        mut analyses: Vec<String>,
+        final_prompt: impl Fn(&[String]) -> String,
+        format: Option<&serde_json::Value>,
+    ) -> Result<(String, bool, usize), Box<dyn std::error::Error>> {
+        let budget = self.token_budget()?;
+        let m = self.messages;
+        let mut complete = true;
+        let mut depth = 1;
+        while analyses.len() > 1 && !budget.fits(&final_prompt(&analyses)) {
+            let total = analyses.len();
+            let limit = self.content_limit(&self.prompts.build_group_summary_prompt(commit_info, &[], total, total), None)?;
+            let groups = group_analyses(analyses, limit);
+            println!("{}[{}]{} {}", COLOR_YELLOW, m.label_chunk, COLOR_RESET, (m.reducing)(&groups.len(), &depth));
+
+            let count = groups.len();
+            let prompts: Vec<String> = groups
+                .iter()
+                .enumerate()
+                .map(|(i, group)| self.prompts.build_group_summary_prompt(commit_info, group, i + 1, count))
+                .collect();
+            let describe = |i: usize| (m.group_progress)(&(i + 1), &count, &depth);
+            let (summaries, level_complete) = self.call_llm_parallel(&prompts, describe, m.item_group)?;
+
+            analyses = summaries;
+            complete &= level_complete;
+            depth += 1;
+        }
+
+        let response = call_llm(self.backend.as_ref(), &self.settings, &self.cancel, &final_prompt(&analyses), format)?;
+        complete &= !response.contains(m.partial_marker);
+        Ok((clean_response(response), complete, depth))
+    }
+
+    // os prompts vao em paralelo ate o limite de requisicoes por host e as respostas voltam na
+    // ordem deles; uma falha vira texto de erro no lugar da resposta
+    fn call_llm_parallel(
+        &self,
+        prompts: &[String],
+        describe: impl Fn(usize) -> String + Sync,
+        what: &str,
+    ) -> Result<(Vec<String>, bool), Box<dyn std::error::Error>> {
+        // sem prompts nao ha o que falhar; no modo por arquivo todos podem ter ido pela divisao
+        if prompts.is_empty() {
+            return Ok((Vec::new(), true));
+        }
+        let workers = self.settings.max_requests_per_host.min(prompts.len());
+        let next = &AtomicUsize::new(0);
+        let describe = &describe;
+        let (sender, receiver) = mpsc::channel();
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                let sender = sender.clone();
+                scope.spawn(move || loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(prompt) = prompts.get(i) else { break };
+                    println!("{}[{}]{} {}", COLOR_MAGENTA, self.messages.label_proc, COLOR_RESET, describe(i));
+
+                    let result = call_llm(self.backend.as_ref(), &self.settings, &self.cancel, prompt, None).map_err(|e| e.to_string());
+                    sender.send((i, result)).ok();
+                });
+            }
+        });
+        drop(sender);
+
+        if self.cancel.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        let m = self.messages;
+
+        let mut results: Vec<_> = receiver.into_iter().collect();
+        results.sort_by_key(|(i, _)| *i);
+
+        if results.iter().all(|(_, result)| result.is_err()) {
+            return Err((m.all_items_failed)(&what).into());
+        }
+
+        let mut responses = Vec::new();
+        let mut complete = true;
+        for (i, result) in results {
+            match result {
+                Ok(response) => {
+                    complete &= !response.contains(m.partial_marker);
+                    responses.push(clean_response(response));
+                }
+                Err(e) => {
+                    println!("{}[{}]{} {}", COLOR_YELLOW, m.label_error, COLOR_RESET, (m.item_failed)(&what, &(i + 1), &prompts.len(), &e));
+                    complete = false;
+                    responses.push((m.item_error)(&what, &(i + 1), &e));
+                }
+            }
+        }
+        Ok((responses, complete))
+    }
+}
+
+// junta analises vizinhas em grupos que caibam em `limit` tokens, sempre com pelo menos duas
+// por grupo; assim cada nivel tem no maximo metade das analises do anterior
+fn group_analyses(analyses: Vec<String>, limit: usize) -> Vec<Vec<String>> {
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let mut tokens = 0;
+
+    for analysis in analyses {
+        let cost = estimate_tokens(&analysis) + GROUP_ITEM_TOKENS;
+        match groups.last_mut() {
+            Some(group) if group.len() < 2 || tokens + cost <= limit => {
+                group.push(analysis);
+                tokens += cost;
+            }
+            _ => {
+                groups.push(vec![analysis]);
+                tokens = cost;
+            }
+        }
+    }
+
+    // uma analise sozinha no fim nao teria o que resumir: leva junto a ultima do grupo anterior,
+    // que continua com pelo menos duas, ou entra nele quando ele so tem duas
+    if groups.len() > 1 && groups.last().is_some_and(|group| group.len() == 1) {
+        let mut last = groups.pop().unwrap();
+        let previous = groups.last_mut().unwrap();
+        if previous.len() > 2 {
+            last.insert(0, previous.pop().unwrap());
+            groups.push(last);
+        } else {
+            previous.extend(last);
+        }
+    }
+    groups
+}
+
+// escreve o bloco de uma vez pra nao intercalar com outros commits em paralelo
+fn print_commit_header(commit_info: &CommitInfo, m: &Messages) {
+    let mut out = std::io::stdout().lock();
+    writeln!(out, "{}{}{} {}", COLOR_GRAY, m.header_commit, COLOR_RESET, commit_info.short_hash).ok();
+    writeln!(out, "{}{}{} {}", COLOR_WHITE, m.header_message, COLOR_RESET, commit_info.message).ok();
+    writeln!(out, "{}{}{} {} {}{}{} {}", COLOR_MAGENTA, m.header_author, COLOR_RESET, commit_info.author, COLOR_GRAY, m.header_at, COLOR_RESET, commit_info.date).ok();
+    writeln!(out, "{}{}{} +{} {}-{}{} {}",
+             COLOR_GREEN, m.header_changes, COLOR_RESET, commit_info.insertions, COLOR_RED, commit_info.deletions, COLOR_RESET,
+             (m.header_lines_in_files)(&commit_info.files_changed.len())).ok();
+}
+
+fn report_excluded(excluded: &[ExcludedFile], m: &Messages) {
+    if !excluded.is_empty() {
+        println!("{}[{}]{} {}", COLOR_GRAY, m.label_filter, COLOR_RESET, (m.excluded_by_filters)(&excluded.len()));
+    }
+}
+
+fn code_list(paths: &[String]) -> String {
+    paths.iter().map(|p| format!("`{}`", p)).collect::<Vec<_>>().join(", ")
+}
+
+// a analise de cada grupo fica embaixo do primeiro arquivo dele; os outros apontam pra ela
+fn render_files_with_analyses(files: &[FileDiff], groups: &[DiffChunk], analyses: &[String], m: &Messages) -> String {
+    files
+        .iter()
+        .map(|file| {
+            let group = groups.iter().position(|g| g.files.iter().any(|p| p == file.path()));
+            let note = match group {
+                Some(i) if groups[i].files[0] == file.path() => analyses[i].clone(),
+                Some(i) => (m.analyzed_with)(&groups[i].files[0]),
+                None => m.not_analyzed.to_string(),
+            };
+            render_file_with_note(file, &note, m)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BackendKind;
+    use crate::llm::{ContextFuture, EchoBackend, LlmFuture};
+    use serde_json::Value;
+    use std::sync::{Arc, Mutex};
+    use crate::test_support::{commit, git, new_file_diff, temp_repo};
+
+    // responde sempre o mesmo texto e guarda os prompts, pra forcar varios niveis de resumo
+    struct FixedBackend {
+        response: String,
+        prompts: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl LlmBackend for FixedBackend {
+        fn label(&self) -> &'static str {
+            "FIXED"
+        }
+
+        fn complete<'a>(&'a self, prompt: &'a str, _format: Option<&'a Value>) -> LlmFuture<'a> {
+            self.prompts.lock().unwrap().push(prompt.to_string());
+            let response = self.response.clone();
+            Box::pin(async move { Ok(response) })
+        }
+
+        fn context_window(&self) -> ContextFuture<'_> {
+            Box::pin(async { 4096 })
+        }
+    }
+
+    fn analysis_of(tokens: usize) -> String {
+        "x".repeat(tokens * 3)
+    }
+
+    fn echo_analyzer(num_ctx: usize, per_file: bool, structured: bool) -> Analyzer {
+        let settings = Settings {
+            backend: BackendKind::Echo,
+            num_ctx: Some(num_ctx),
+            per_file,
+            structured,
+            ..Settings::default()
+        };
+        let backend = EchoBackend::new(&settings);
+        Analyzer::with_backend(settings, Box::new(backend)).unwrap()
+    }
+
+    #[test]
+    fn per_file_with_only_oversized_files_goes_through_chunks() {
+        let analyzer = echo_analyzer(4096, true, false);
+        let diff = new_file_diff("src/grande.rs", 3000);
+
+        let report = analyzer
+            .analyze_diff(commit("adiciona arquivo grande", &["src/grande.rs"], 0), &diff, AnalysisKind::Commit)
+            .unwrap()
+            .expect("há mudanças");
+
+        assert!(report.complete);
+        let reduction = report.reduction.expect("dividido");
+        assert!(reduction.per_file);
+        assert_eq!(reduction.chunks, 1);
+    }
+
+    #[test]
+    fn per_file_analyzes_each_small_file() {
+        let analyzer = echo_analyzer(4096, true, false);
+        let diff = new_file_diff("src/a.rs", 5) + &new_file_diff("docs/b.md", 5);
+
+        let report = analyzer
+            .analyze_diff(commit("adiciona dois arquivos", &["src/a.rs", "docs/b.md"], 0), &diff, AnalysisKind::Commit)
+            .unwrap()
+            .expect("há mudanças");
+
+        assert!(report.complete);
+        assert_eq!(report.reduction.map(|r| r.chunks), Some(2));
+        assert_eq!(report.files.len(), 2);
+    }
+
+    #[test]
+    fn structured_per_file_only_asks_json_for_the_synthesis() {
+        let analyzer = echo_analyzer(4096, true, true);
+        let diff = new_file_diff("src/grande.rs", 3000) + &new_file_diff("src/pequeno.rs", 5);
+
+        let report = analyzer
+            .analyze_diff(commit("adiciona arquivo grande", &["src/grande.rs", "src/pequeno.rs"], 0), &diff, AnalysisKind::Commit)
+            .unwrap()
+            .expect("há mudanças");
+
+        assert!(report.structured.is_some());
+        // as analises de cada arquivo, embaixo do diff, continuam em texto
+        assert!(!report.formatted_diff.contains("\"purpose\""));
+    }
+
+    #[test]
+    fn locale_comes_from_the_settings() {
+        let settings = Settings { backend: BackendKind::Echo, num_ctx: Some(4096), locale: Locale::En, ..Settings::default() };
+        let backend = EchoBackend::new(&settings);
+        let analyzer = Analyzer::with_backend(settings, Box::new(backend)).unwrap();
+
+        let report = analyzer
+            .analyze_diff(commit("adiciona arquivo", &["src/a.rs"], 0), &new_file_diff("src/a.rs", 5), AnalysisKind::Commit)
+            .unwrap()
+            .expect("há mudanças");
+
+        // sem set_locale: prompt, descricao dos arquivos e relatorio seguem as configuracoes
+        assert!(report.analysis.starts_with("[echo] prompt with"));
+        assert!(report.formatted_diff.starts_with("### File: `src/a.rs`\n*New file.*"));
+        assert!(report.to_markdown().contains("## Commit Information"));
+    }
+
+    #[test]
+    fn analyze_commit_accepts_any_revision() {
+        let repo = temp_repo();
+        let settings = Settings { backend: BackendKind::Echo, num_ctx: Some(4096), repo_path: repo.path().to_path_buf(), ..Settings::default() };
+        let backend = EchoBackend::new(&settings);
+        let analyzer = Analyzer::with_backend(settings, Box::new(backend)).unwrap();
+
+        let report = analyzer.analyze_commit("HEAD").unwrap().expect("há mudanças");
+        assert_eq!(report.commit.hash.len(), 40);
+        assert_eq!(report.commit.short_hash, report.commit.hash[..12]);
+        assert_eq!(report.commit.message, "adiciona soma");
+    }
+
+    #[test]
+    fn analyze_pending_skips_an_empty_stage() {
+        let repo = temp_repo();
+        let settings = Settings { backend: BackendKind::Echo, num_ctx: Some(4096), repo_path: repo.path().to_path_buf(), ..Settings::default() };
+        let backend = EchoBackend::new(&settings);
+        let analyzer = Analyzer::with_backend(settings, Box::new(backend)).unwrap();
+
+        assert!(analyzer.analyze_pending(PendingChanges::Staged, "").unwrap().is_none());
+
+        std::fs::write(repo.path().join("src/lib.rs"), "pub fn soma(a: i32, b: i32) -> i32 {\n    b + a\n}\n").unwrap();
+        assert!(analyzer.analyze_pending(PendingChanges::Staged, "").unwrap().is_none());
+
+        git(repo.path(), &["add", "."]);
+        let report = analyzer.analyze_pending(PendingChanges::Staged, "troca a ordem").unwrap().expect("há mudanças staged");
+        assert_eq!(report.commit.files_changed, ["src/lib.rs"]);
+        assert_eq!((report.commit.insertions, report.commit.deletions), (1, 1));
+        assert_eq!(report.commit.message, "troca a ordem");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn runs_inside_a_multi_thread_runtime() {
+        let analyzer = echo_analyzer(4096, false, false);
+        let report = analyzer
+            .analyze_diff(commit("adiciona arquivo", &["src/a.rs"], 0), &new_file_diff("src/a.rs", 5), AnalysisKind::Commit)
+            .unwrap();
+        assert!(report.is_some());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn current_thread_runtime_is_an_error_not_a_panic() {
+        let analyzer = echo_analyzer(4096, false, false);
+        let result = analyzer.analyze_diff(commit("adiciona arquivo", &["src/a.rs"], 0), &new_file_diff("src/a.rs", 5), AnalysisKind::Commit);
+        assert_eq!(result.unwrap_err().to_string(), analyzer.messages.current_thread_runtime);
+    }
+
+    #[test]
+    fn chunks_past_the_limit_leave_the_analysis_incomplete() {
+        let settings = Settings { backend: BackendKind::Echo, num_ctx: Some(4096), max_chunks: 2, ..Settings::default() };
+        let backend = EchoBackend::new(&settings);
+        let analyzer = Analyzer::with_backend(settings, Box::new(backend)).unwrap();
+        let diff = new_file_diff("src/grande.rs", 3000);
+
+        let report = analyzer
+            .analyze_diff(commit("adiciona arquivo grande", &["src/grande.rs"], 0), &diff, AnalysisKind::Commit)
+            .unwrap()
+            .expect("há mudanças");
+
+        assert_eq!(report.reduction.map(|r| r.chunks), Some(2));
+        assert!(!report.complete);
+    }
+
+    #[test]
+    fn files_past_the_limit_leave_the_analysis_incomplete() {
+        let settings = Settings { backend: BackendKind::Echo, num_ctx: Some(4096), per_file: true, max_chunks: 1, ..Settings::default() };
+        let backend = EchoBackend::new(&settings);
+        let analyzer = Analyzer::with_backend(settings, Box::new(backend)).unwrap();
+        let diff = new_file_diff("src/a.rs", 5) + &new_file_diff("docs/b.md", 5);
+
+        let report = analyzer
+            .analyze_diff(commit("adiciona dois arquivos", &["src/a.rs", "docs/b.md"], 0), &diff, AnalysisKind::Commit)
+            .unwrap()
+            .expect("há mudanças");
+
+        assert_eq!(report.reduction.map(|r| r.chunks), Some(1));
+        assert!(!report.complete);
+        assert!(report.formatted_diff.contains(analyzer.messages.not_analyzed));
+    }
+
+    #[test]
+    fn analysis_groups_fit_the_limit_and_keep_the_order() {
+        let analyses: Vec<String> = (0..10).map(|i| format!("{}{}", i, analysis_of(55))).collect();
+        let groups = group_analyses(analyses.clone(), 200);
+
+        assert_eq!(groups.concat(), analyses);
+        assert!(groups.len() <= analyses.len() / 2);
+        for group in &groups {
+            assert!(group.len() >= 2);
+            let tokens: usize = group.iter().map(|a| estimate_tokens(a) + GROUP_ITEM_TOKENS).sum();
+            assert!(tokens <= 200, "grupo com {} tokens", tokens);
+        }
+    }
+
+    #[test]
+    fn reduce_summarizes_level_by_level_until_it_fits() {
+        let prompts = Arc::new(Mutex::new(Vec::new()));
+        let backend = FixedBackend { response: analysis_of(1100), prompts: prompts.clone() };
+        let settings = Settings { backend: BackendKind::Echo, ..Settings::default() };
+        let analyzer = Analyzer::with_backend(settings, Box::new(backend)).unwrap();
+        let info = commit("muda muita coisa", &["src/a.rs"], 0);
+
+        let final_prompt = |analyses: &[String]| analyzer.prompts.build_summary_prompt(AnalysisKind::Commit, &info, analyses, false);
+        let (analysis, complete, depth) = analyzer.reduce(&info, vec![analysis_of(1100); 16], final_prompt, None).unwrap();
+
+        assert!(complete);
+        assert_eq!(analysis, analysis_of(1100));
+        assert!(depth > 2, "so {} nivel(is) de resumo", depth);
+        // cada prompt, dos grupos e o final, coube na janela
+        let budget = analyzer.token_budget().unwrap();
+        assert!(prompts.lock().unwrap().iter().all(|prompt| budget.fits(prompt)));
+    }
+
+    #[test]
+    fn echo_chunk_analyses_are_reduced_before_the_summary() {
+        // janela pequena: muitos pedacos, e as respostas deles nao cabem juntas num prompt so
+        let settings = Settings { backend: BackendKind::Echo, num_ctx: Some(1400), answer_tokens: 64, ..Settings::default() };
+        let backend = EchoBackend::new(&settings);
+        let analyzer = Analyzer::with_backend(settings, Box::new(backend)).unwrap();
+        let diff = new_file_diff("src/grande.rs", 3000);
+
+        let report = analyzer
+            .analyze_diff(commit("adiciona arquivo grande", &["src/grande.rs"], 0), &diff, AnalysisKind::Commit)
+            .unwrap()
+            .expect("há mudanças");
+
+        let reduction = report.reduction.expect("dividido");
+        assert!(reduction.depth > 1, "{:?}", reduction);
+        assert!(report.complete);
+    }
+}
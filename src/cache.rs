@@ -0,0 +1,265 @@
+use crate::analysis::Analysis;
+use crate::analyzer::AnalysisReport;
+use crate::config::Settings;
+use crate::git;
+use crate::prompt::PromptTemplates;
+use crate::report::write_atomic;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+// fica dentro do .git, entao nunca aparece no working tree
+const CACHE_DIR: &str = "diff-extractor/cache";
+
+/// Tudo que muda o resultado de uma análise; se algo difere, o commit é analisado de novo.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheKey {
+    pub commit: String,
+    pub backend: String,
+    pub model: String,
+    pub prompt_hash: String,
+    pub profile: String,
+    pub locale: String,
+    pub max_diff_size: Option<usize>,
+    pub chunk_size: Option<usize>,
+    pub num_ctx: Option<usize>,
+    pub answer_tokens: usize,
+    pub max_chunks: usize,
+    pub per_file: bool,
+    pub structured: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub default_excludes: bool,
+}
+
+impl CacheKey {
+    pub fn new(commit: &str, settings: &Settings) -> Self {
+        CacheKey {
+            commit: commit.to_string(),
+            backend: settings.backend.name().to_string(),
+            model: settings.model.clone(),
+            // templates invalidos ja barram a analise na validacao
+            prompt_hash: PromptTemplates::load(settings).map(|t| t.fingerprint().to_string()).unwrap_or_default(),
+            profile: settings.profile.name().to_string(),
+            locale: settings.locale.name().to_string(),
+            max_diff_size: settings.max_diff_size,
+            chunk_size: settings.chunk_size,
+            num_ctx: settings.num_ctx,
+            answer_tokens: settings.answer_tokens,
+            max_chunks: settings.max_chunks,
+            per_file: settings.per_file,
+            structured: settings.structured,
+            include: settings.include.clone(),
+            exclude: settings.exclude.clone(),
+            default_excludes: settings.default_excludes,
+        }
+    }
+
+    fn digest(&self) -> String {
+        let json = serde_json::to_string(self).expect("chave do cache serializável");
+        format!("{:x}", Sha256::digest(json.as_bytes()))
+    }
+
+    // mesmo commit, mas com outro modelo/prompt/configuracao de pedacos
+    pub fn same_settings(&self, other: &CacheKey) -> bool {
+        CacheKey { commit: other.commit.clone(), ..self.clone() } == *other
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub key: CacheKey,
+    pub created_at: String,
+    /// Relatório gerado quando a análise foi feita, com caminho absoluto: o cache vale
+    /// de qualquer diretório, o relatório também tem que valer.
+    pub report_file: PathBuf,
+    pub analysis: String,
+    /// Campos da análise estruturada, quando a resposta seguiu o esquema.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<Analysis>,
+}
+
+/// Arquivo do cache; `entry` é `None` quando não dá pra ler.
+pub struct StoredEntry {
+    pub path: PathBuf,
+    pub entry: Option<CacheEntry>,
+}
+
+pub struct AnalysisCache {
+    dir: PathBuf,
+}
+
+impl AnalysisCache {
+    pub fn open(repo_path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(AnalysisCache {
+            dir: git::git_path(repo_path, CACHE_DIR)?,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(format!("{}_{}.json", key.commit, &key.digest()[..16]))
+    }
+
+    /// Entrada da chave; sem o relatório ela não serve, já que o cache não guarda o diff
+    /// pra montar outro.
+    pub fn get(&self, key: &CacheKey) -> Option<CacheEntry> {
+        let content = std::fs::read_to_string(self.entry_path(key)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+        (entry.key == *key && entry.report_file.is_file()).then_some(entry)
+    }
+
+    pub fn put(&self, key: CacheKey, report_file: &Path, report: &AnalysisReport) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(&self.dir)?;
+
+        let path = self.entry_path(&key);
+        let entry = CacheEntry {
+            key,
+            created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            report_file: std::fs::canonicalize(report_file)?,
+            analysis: report.analysis.clone(),
+            structured: report.structured.clone(),
+        };
+        write_atomic(&path, &serde_json::to_string_pretty(&entry)?)?;
+        Ok(())
+    }
+
+    /// Entradas em ordem de criação.
+    pub fn entries(&self) -> Result<Vec<StoredEntry>, Box<dyn std::error::Error>> {
+        if !self.dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for dir_entry in std::fs::read_dir(&self.dir)? {
+            let path = dir_entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let entry = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<CacheEntry>(&content).ok());
+            entries.push(StoredEntry { path, entry });
+        }
+        entries.sort_by_cached_key(|stored| stored.entry.as_ref().map(|e| e.created_at.clone()));
+        Ok(entries)
+    }
+
+    /// Com `all` remove tudo; senão só as entradas ilegíveis, de commits que não existem
+    /// mais ou feitas com outro modelo, prompt ou configuração de pedaços.
+    pub fn prune(&self, repo_path: &Path, settings: &Settings, all: bool) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut removed = 0;
+
+        for StoredEntry { path, entry } in self.entries()? {
+            let keep = !all
+                && entry.is_some_and(|entry| {
+                    let current = CacheKey::new(&entry.key.commit, settings);
+                    entry.key.same_settings(&current) && git::commit_exists(repo_path, &entry.key.commit)
+                });
+            if !keep {
+                std::fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BackendKind;
+    use crate::llm::EchoBackend;
+    use crate::test_support::{temp_repo, TempDir};
+    use crate::Analyzer;
+
+    fn settings(repo: &TempDir) -> Settings {
+        Settings { backend: BackendKind::Echo, num_ctx: Some(4096), repo_path: repo.path().to_path_buf(), ..Settings::default() }
+    }
+
+    // analisa o HEAD, grava o relatorio no repositorio e guarda no cache
+    fn cached_head(repo: &TempDir, settings: &Settings) -> (AnalysisCache, CacheKey) {
+        let backend = EchoBackend::new(settings);
+        let analyzer = Analyzer::with_backend(settings.clone(), Box::new(backend)).unwrap();
+        let report = analyzer.analyze_commit("HEAD").unwrap().expect("há mudanças");
+        let report_file = repo.path().join(report.filename());
+        std::fs::write(&report_file, report.to_markdown()).unwrap();
+
+        let cache = AnalysisCache::open(repo.path()).unwrap();
+        let key = CacheKey::new(&report.commit.hash, settings);
+        cache.put(key.clone(), &report_file, &report).unwrap();
+        (cache, key)
+    }
+
+    #[test]
+    fn put_then_get_is_a_hit() {
+        let repo = temp_repo();
+        let settings = settings(&repo);
+        let (cache, key) = cached_head(&repo, &settings);
+
+        let entry = cache.get(&key).expect("no cache");
+        assert!(entry.report_file.is_absolute());
+        assert!(entry.analysis.starts_with("[echo]"));
+        assert!(cache.dir().starts_with(repo.path().join(".git")));
+    }
+
+    #[test]
+    fn other_settings_or_prompts_are_a_miss() {
+        let repo = temp_repo();
+        let settings = settings(&repo);
+        let (cache, key) = cached_head(&repo, &settings);
+
+        let other_model = Settings { model: "outro".to_string(), ..settings.clone() };
+        assert!(cache.get(&CacheKey::new(&key.commit, &other_model)).is_none());
+
+        let other_context = Settings { project_context: Some("outro projeto".to_string()), ..settings.clone() };
+        let other_key = CacheKey::new(&key.commit, &other_context);
+        assert_ne!(other_key.prompt_hash, key.prompt_hash);
+        assert!(cache.get(&other_key).is_none());
+
+        let templates = TempDir::new();
+        std::fs::write(templates.path().join("commit_message.txt"), "Mensagem para:\n{{diff}}\n").unwrap();
+        let other_templates = Settings { templates: Some(templates.path().to_path_buf()), ..settings.clone() };
+        assert_ne!(CacheKey::new(&key.commit, &other_templates).prompt_hash, key.prompt_hash);
+    }
+
+    #[test]
+    fn deleted_report_is_a_miss() {
+        let repo = temp_repo();
+        let settings = settings(&repo);
+        let (cache, key) = cached_head(&repo, &settings);
+
+        std::fs::remove_file(cache.get(&key).unwrap().report_file).unwrap();
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn prune_keeps_only_current_entries_of_existing_commits() {
+        let repo = temp_repo();
+        let settings = settings(&repo);
+        let (cache, key) = cached_head(&repo, &settings);
+        let (_, stale) = cached_head(&repo, &Settings { model: "outro".to_string(), ..settings.clone() });
+        std::fs::write(cache.dir().join("ilegivel.json"), "{").unwrap();
+
+        // commit que sumiu do repositorio: mesma entrada, outro hash
+        let gone = cache.dir().join(format!("{}.json", "f".repeat(40)));
+        let mut entry = cache.get(&key).unwrap();
+        entry.key.commit = "f".repeat(40);
+        std::fs::write(&gone, serde_json::to_string(&entry).unwrap()).unwrap();
+
+        assert_eq!(cache.prune(repo.path(), &settings, false).unwrap(), 3);
+        assert!(cache.get(&key).is_some());
+        assert!(cache.get(&stale).is_none());
+        assert_eq!(cache.entries().unwrap().len(), 1);
+
+        let report_file = cache.get(&key).unwrap().report_file;
+        assert_eq!(cache.prune(repo.path(), &settings, true).unwrap(), 1);
+        assert!(cache.entries().unwrap().is_empty());
+        // o relatorio nao e do cache e fica
+        assert!(report_file.is_file());
+    }
+}
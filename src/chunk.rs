@@ -0,0 +1,345 @@
+use crate::diff::{DiffLine, FileDiff, Hunk};
+use crate::i18n::Messages;
+use crate::markdown::{render_file_header, render_hunk};
+use crate::tokens::estimate_tokens;
+
+// arquivos pequenos da mesma pasta dividem um pedaco ate esta fracao do limite
+const SMALL_GROUP_SHARE: usize = 4;
+
+#[derive(Debug)]
+pub struct DiffChunk {
+    pub content: String,
+    pub files: Vec<String>,
+    /// Tokens estimados.
+    pub size: usize,
+}
+
+// pedaco em montagem; o cabecalho do arquivo entra sempre que o arquivo aparece pela primeira vez nele
+struct ChunkBuilder<'m> {
+    messages: &'m Messages,
+    content: String,
+    files: Vec<String>,
+    tokens: usize,
+}
+
+impl<'m> ChunkBuilder<'m> {
+    fn new(messages: &'m Messages) -> Self {
+        ChunkBuilder { messages, content: String::new(), files: Vec::new(), tokens: 0 }
+    }
+
+    fn size(&self) -> usize {
+        self.tokens
+    }
+
+    fn cost(&self, file: &FileDiff, block: &str) -> usize {
+        let header = if self.files.last().map(String::as_str) == Some(file.path()) {
+            0
+        } else {
+            estimate_tokens(&render_file_header(file, self.messages))
+        };
+        header + estimate_tokens(block)
+    }
+
+    fn push(&mut self, file: &FileDiff, block: &str) {
+        self.tokens += self.cost(file, block);
+        if self.files.last().map(String::as_str) != Some(file.path()) {
+            self.content.push_str(&render_file_header(file, self.messages));
+            self.files.push(file.path().to_string());
+        }
+        self.content.push_str(block);
+    }
+
+    fn finish(&mut self, chunks: &mut Vec<DiffChunk>) {
+        if self.content.is_empty() {
+            return;
+        }
+        let builder = std::mem::replace(self, ChunkBuilder::new(self.messages));
+        chunks.push(DiffChunk {
+            size: builder.size(),
+            content: builder.content,
+            files: builder.files,
+        });
+    }
+}
+
+/// Divide o diff em pedaços de até `max_tokens` sem cortar hunks nem blocos de código.
+/// Um hunk que sozinho passa do limite é quebrado em hunks menores, cada um com o
+/// nome do arquivo e o próprio cabeçalho `@@`.
+pub fn split_diff_into_chunks(files: &[FileDiff], max_tokens: usize, m: &Messages) -> Vec<DiffChunk> {
+    let mut chunks = Vec::new();
+    let mut current = ChunkBuilder::new(m);
+
+    for file in files {
+        // renomeacao, binario...: so o cabecalho
+        if file.hunks.is_empty() {
+            if current.size() + current.cost(file, "\n") > max_tokens {
+                current.finish(&mut chunks);
+            }
+            current.push(file, "\n");
+            continue;
+        }
+
+        for hunk in &file.hunks {
+            let block = render_hunk(hunk);
+            if current.size() + current.cost(file, &block) <= max_tokens {
+                current.push(file, &block);
+                continue;
+            }
+
+            current.finish(&mut chunks);
+            if current.cost(file, &block) <= max_tokens {
+                current.push(file, &block);
+                continue;
+            }
+
+            // nem sozinho cabe: cada parte vira um pedaco proprio
+            let budget = max_tokens.saturating_sub(estimate_tokens(&render_file_header(file, m)));
+            for part in split_hunk(hunk, budget) {
+                current.push(file, &render_hunk(&part));
+                current.finish(&mut chunks);
+            }
+        }
+    }
+
+    current.finish(&mut chunks);
+    chunks
+}
+
+/// Agrupa o diff por arquivo para o modo por arquivo: cada arquivo fica num pedaço
+/// próprio, exceto arquivos pequenos da mesma pasta, que vão juntos enquanto somarem até
+/// um quarto de `max_tokens`. Um arquivo maior que `max_tokens` vem sozinho mesmo assim;
+/// quem chama decide como dividir.
+pub fn group_related_files(files: &[FileDiff], max_tokens: usize, m: &Messages) -> Vec<DiffChunk> {
+    let small = max_tokens / SMALL_GROUP_SHARE;
+    let mut chunks = Vec::new();
+    let mut current = ChunkBuilder::new(m);
+    let mut current_dir = None;
+
+    for file in files {
+        let block: String = file.hunks.iter().map(render_hunk).collect();
+        let tokens = current.cost(file, &block);
+        let dir = parent_dir(file.path());
+
+        let joins = current_dir == Some(dir) && tokens <= small && current.size() + tokens <= small;
+        if !joins {
+            current.finish(&mut chunks);
+        }
+        current.push(file, &block);
+        current_dir = (tokens <= small).then_some(dir);
+    }
+
+    current.finish(&mut chunks);
+    chunks
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+// quebra o hunk em hunks menores com os intervalos de linha recalculados
+fn split_hunk(hunk: &Hunk, max_tokens: usize) -> Vec<Hunk> {
+    let mut parts = Vec::new();
+    // com contagem zero o git aponta pra linha anterior; aqui as posicoes sao sempre a proxima linha
+    let first = |start: u32, count: u32| if count == 0 { start + 1 } else { start };
+    let mut part = sub_hunk(hunk, first(hunk.old_start, hunk.old_lines), first(hunk.new_start, hunk.new_lines));
+
+    for line in &hunk.lines {
+        let mut candidate = part.clone();
+        push_line(&mut candidate, line.clone());
+
+        if estimate_tokens(&render_hunk(&candidate)) > max_tokens && !part.lines.is_empty() {
+            let (old_next, new_next) = next_positions(&part);
+            parts.push(normalized(part));
+            part = sub_hunk(hunk, old_next, new_next);
+            push_line(&mut part, line.clone());
+        } else {
+            part = candidate;
+        }
+    }
+
+    if !part.lines.is_empty() {
+        parts.push(normalized(part));
+    }
+    parts
+}
+
+fn normalized(mut part: Hunk) -> Hunk {
+    if part.old_lines == 0 {
+        part.old_start = part.old_start.saturating_sub(1);
+    }
+    if part.new_lines == 0 {
+        part.new_start = part.new_start.saturating_sub(1);
+    }
+    part
+}
+
+fn sub_hunk(hunk: &Hunk, old_start: u32, new_start: u32) -> Hunk {
+    Hunk {
+        old_start,
+        old_lines: 0,
+        new_start,
+        new_lines: 0,
+        context: hunk.context.clone(),
+        lines: Vec::new(),
+    }
+}
+
+fn push_line(part: &mut Hunk, line: DiffLine) {
+    // o inicio de cada lado e a primeira linha que realmente aparece nele
+    if part.old_lines == 0 && let Some(old) = line.old_line {
+        part.old_start = old;
+    }
+    if part.new_lines == 0 && let Some(new) = line.new_line {
+        part.new_start = new;
+    }
+    part.old_lines += u32::from(line.old_line.is_some());
+    part.new_lines += u32::from(line.new_line.is_some());
+    part.lines.push(line);
+}
+
+// onde a proxima parte comeca em cada lado
+fn next_positions(part: &Hunk) -> (u32, u32) {
+    let old = if part.old_lines == 0 { part.old_start } else { part.old_start + part.old_lines };
+    let new = if part.new_lines == 0 { part.new_start } else { part.new_start + part.new_lines };
+    (old, new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parse_unified_diff;
+    use crate::i18n::Locale;
+    use crate::markdown::render_files;
+    use crate::test_support::new_file_diff;
+
+    const HUNK: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -10,5 +10,6 @@ fn calcula() {
+     let a = 1;
+-    let b = 2;
++    let b = 3;
++    let c = 4;
+     let d = 5;
+-    let e = 6;
++    let e = 7;
+";
+
+    #[test]
+    fn split_hunk_recalculates_line_ranges() {
+        let files = parse_unified_diff(HUNK);
+        let hunk = &files[0].hunks[0];
+
+        // limite menor que qualquer linha: uma linha por parte
+        let headers: Vec<String> = split_hunk(hunk, 1).iter().map(Hunk::header).collect();
+        assert_eq!(
+            headers,
+            [
+                "@@ -10,1 +10,1 @@ fn calcula() {",
+                "@@ -11,1 +10,0 @@ fn calcula() {",
+                "@@ -11,0 +11,1 @@ fn calcula() {",
+                "@@ -11,0 +12,1 @@ fn calcula() {",
+                "@@ -12,1 +13,1 @@ fn calcula() {",
+                "@@ -13,1 +13,0 @@ fn calcula() {",
+                "@@ -13,0 +14,1 @@ fn calcula() {",
+            ]
+        );
+    }
+
+    #[test]
+    fn split_hunk_keeps_every_line_and_consistent_counts() {
+        let files = parse_unified_diff(HUNK);
+        let hunk = &files[0].hunks[0];
+
+        for max_tokens in [1, 20, 30, 1000] {
+            let parts = split_hunk(hunk, max_tokens);
+            let lines: Vec<DiffLine> = parts.iter().flat_map(|p| p.lines.clone()).collect();
+            assert_eq!(lines, hunk.lines, "limite {max_tokens}");
+
+            for part in &parts {
+                let old: Vec<u32> = part.lines.iter().filter_map(|l| l.old_line).collect();
+                let new: Vec<u32> = part.lines.iter().filter_map(|l| l.new_line).collect();
+                assert_eq!((part.old_lines, part.new_lines), (old.len() as u32, new.len() as u32));
+                if let Some(first) = old.first() {
+                    assert_eq!(part.old_start, *first, "{}", part.header());
+                }
+                if let Some(first) = new.first() {
+                    assert_eq!(part.new_start, *first, "{}", part.header());
+                }
+            }
+        }
+        assert_eq!(split_hunk(hunk, 1000).len(), 1);
+    }
+
+    // linhas de dentro das cercas, sem o cabecalho @@ que muda quando o hunk e quebrado
+    fn hunk_lines(markdown: &str) -> Vec<&str> {
+        let mut lines = Vec::new();
+        let mut fence: Option<&str> = None;
+        for line in markdown.lines() {
+            match fence {
+                None if line.starts_with("```") => {
+                    let backticks = line.trim_end_matches("diff");
+                    assert_eq!(backticks.len() + "diff".len(), line.len(), "cerca sem ```diff: {line}");
+                    fence = Some(backticks);
+                }
+                Some(open) if line == open => fence = None,
+                Some(_) if !line.starts_with("@@") => lines.push(line),
+                _ => {}
+            }
+        }
+        assert_eq!(fence, None, "cerca sem fechamento");
+        lines
+    }
+
+    #[test]
+    fn split_diff_keeps_headers_fences_and_every_line() {
+        let readme = "\
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -1,2 +1,5 @@
+ # Projeto
++```rust
++fn main() {}
++```
+ Fim
+";
+        let diff = [new_file_diff("src/a.rs", 30), HUNK.to_string(), readme.to_string(), new_file_diff("src/b.rs", 3)].concat();
+        let files = parse_unified_diff(&diff);
+        let m = Locale::PtBr.messages();
+
+        let chunks = split_diff_into_chunks(&files, 60, m);
+        assert!(chunks.len() > files.len(), "{} pedaços", chunks.len());
+
+        let mut lines = Vec::new();
+        for chunk in &chunks {
+            assert!(chunk.content.starts_with(&format!("### Arquivo: `{}`", chunk.files[0])), "{}", chunk.content);
+            lines.extend(hunk_lines(&chunk.content));
+        }
+        assert_eq!(lines, hunk_lines(&render_files(&files, m)));
+    }
+
+    #[test]
+    fn groups_only_small_files_of_the_same_directory() {
+        let diff = [
+            new_file_diff("src/a.rs", 1),
+            new_file_diff("src/b.rs", 1),
+            new_file_diff("lib/c.rs", 1),
+            new_file_diff("src/grande.rs", 40),
+            new_file_diff("src/d.rs", 1),
+            new_file_diff("src/enorme.rs", 200),
+        ]
+        .concat();
+        let files = parse_unified_diff(&diff);
+
+        let chunks = group_related_files(&files, 400, Locale::PtBr.messages());
+        let grouped: Vec<Vec<&str>> = chunks.iter().map(|c| c.files.iter().map(String::as_str).collect()).collect();
+        assert_eq!(
+            grouped,
+            vec![vec!["src/a.rs", "src/b.rs"], vec!["lib/c.rs"], vec!["src/grande.rs"], vec!["src/d.rs"], vec!["src/enorme.rs"]]
+        );
+        // o que nao cabe vem inteiro; quem chama decide como dividir
+        assert!(chunks[4].size > 400);
+    }
+}
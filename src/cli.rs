@@ -0,0 +1,444 @@
+use git_diff_extractor_rust::config::{BackendKind, PartialSettings};
+use git_diff_extractor_rust::git::CommitQuery;
+use git_diff_extractor_rust::i18n::{Locale, Messages};
+use git_diff_extractor_rust::profile::Profile;
+use git_diff_extractor_rust::prompt::TEMPLATE_NAMES;
+use clap::builder::{PossibleValuesParser, Str};
+use clap::{Args, Command, CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(name = "git-diff-extractor", version)]
+pub struct Cli {
+    #[command(flatten)]
+    pub settings: SettingsArgs,
+
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    // atalho para `analyze -n`
+    #[arg(hide = true, value_parser = clap::value_parser!(u32).range(1..))]
+    pub count: Option<u32>,
+}
+
+impl Cli {
+    /// Lê os argumentos do processo, com a ajuda e os nomes dos valores no idioma de `m`.
+    pub fn parse_localized(m: &Messages) -> Cli {
+        let mut command = localized_command(m);
+        let matches = command.get_matches_mut();
+        Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.format(&mut command).exit())
+    }
+}
+
+pub fn localized_command(m: &Messages) -> Command {
+    localize(Cli::command().about(m.cli_about), "", m)
+}
+
+// `path` e o caminho do subcomando no catalogo, como "cache prune"
+fn localize(command: Command, path: &str, m: &Messages) -> Command {
+    command
+        .mut_args(|arg| {
+            let help = cli_help(m, path, arg.get_id().as_str());
+            let arg = match help {
+                Some(help) => arg.help(help),
+                None => arg,
+            };
+            let value_names: Option<Vec<Str>> = arg.get_value_names().map(|names| {
+                names.iter().map(|name| value_name(m, name.as_str()).map_or_else(|| name.clone(), Str::from)).collect()
+            });
+            match value_names {
+                Some(names) => arg.value_names(names),
+                None => arg,
+            }
+        })
+        .mut_subcommands(|sub| {
+            let sub_path = join_path(path, sub.get_name());
+            let sub = match cli_help(m, path, sub.get_name()) {
+                Some(about) => sub.about(about),
+                None => sub,
+            };
+            localize(sub, &sub_path, m)
+        })
+}
+
+// primeiro o texto do subcomando, depois o compartilhado
+fn cli_help(m: &Messages, path: &str, name: &str) -> Option<&'static str> {
+    let find = |key: &str| m.cli_help.iter().find(|(k, _)| *k == key).map(|(_, text)| *text);
+    find(&join_path(path, name)).or_else(|| find(name))
+}
+
+fn value_name(m: &Messages, name: &str) -> Option<&'static str> {
+    m.cli_value_names.iter().find(|(k, _)| *k == name).map(|(_, text)| *text)
+}
+
+fn join_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} {}", path, name)
+    }
+}
+
+/// `--repo` e `--locale` lidos antes do clap, para escolher o idioma da ajuda e dos erros dele.
+pub fn early_settings(args: &[String]) -> PartialSettings {
+    let mut partial = PartialSettings::default();
+    // depois de `--` so vem caminho
+    let mut args = args.iter().take_while(|arg| *arg != "--");
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        if name != "--repo" && name != "--locale" {
+            continue;
+        }
+        let value = inline.or_else(|| args.next().cloned());
+        if name == "--repo" {
+            partial.repo = value.map(PathBuf::from);
+        } else {
+            partial.locale = value.and_then(|v| v.parse().ok());
+        }
+    }
+    partial
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    Analyze {
+        #[arg(short = 'n', long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        count: Option<u32>,
+
+        #[arg(long)]
+        force: bool,
+
+        #[command(flatten)]
+        selection: SelectionArgs,
+    },
+    Range {
+        range: String,
+
+        #[arg(short = 'n', long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        count: Option<u32>,
+
+        #[arg(long)]
+        force: bool,
+
+        #[command(flatten)]
+        selection: SelectionArgs,
+    },
+    Report {
+        rev: String,
+
+        #[arg(long)]
+        force: bool,
+    },
+    Staged {
+        #[arg(long)]
+        worktree: bool,
+
+        #[arg(short, long, value_name = "TEXTO")]
+        message: Option<String>,
+
+        #[arg(short, long, value_name = "ARQUIVO")]
+        output: Option<PathBuf>,
+    },
+    Pr {
+        base: String,
+
+        #[arg(default_value = "HEAD")]
+        head: String,
+
+        #[arg(short, long, value_name = "ARQUIVO")]
+        output: Option<PathBuf>,
+    },
+    Message {
+        #[arg(long)]
+        conventional: bool,
+    },
+    PrepareCommitMsg {
+        file: PathBuf,
+
+        source: Option<String>,
+
+        sha: Option<String>,
+
+        #[arg(long)]
+        conventional: bool,
+    },
+    InstallHook {
+        #[arg(long)]
+        conventional: bool,
+
+        #[arg(long)]
+        uninstall: bool,
+    },
+    Render {
+        #[arg(default_value = "HEAD", conflicts_with = "staged")]
+        rev: String,
+
+        #[arg(short, long, default_value = "analysis", value_parser = PossibleValuesParser::new(TEMPLATE_NAMES))]
+        template: String,
+
+        #[arg(long)]
+        staged: bool,
+    },
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommands,
+    },
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    Show,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommands {
+    Show,
+    Prune {
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Args, Debug, Default)]
+pub struct SettingsArgs {
+    #[arg(long, global = true, value_name = "CAMINHO")]
+    pub repo: Option<PathBuf>,
+
+    #[arg(long, global = true, value_name = "NOME", value_parser = parse_backend)]
+    pub backend: Option<BackendKind>,
+
+    #[arg(long, global = true, value_name = "NOME")]
+    pub model: Option<String>,
+
+    #[arg(long, global = true, value_name = "URL")]
+    pub ollama_url: Option<String>,
+
+    #[arg(long, global = true, value_name = "CHAVE")]
+    pub api_key: Option<String>,
+
+    #[arg(long, global = true, value_name = "TOKENS")]
+    pub max_diff_size: Option<usize>,
+
+    #[arg(long, global = true, value_name = "TOKENS")]
+    pub chunk_size: Option<usize>,
+
+    #[arg(long, global = true, value_name = "TOKENS")]
+    pub num_ctx: Option<usize>,
+
+    #[arg(long, global = true, value_name = "TOKENS")]
+    pub answer_tokens: Option<usize>,
+
+    #[arg(long, global = true, value_name = "N")]
+    pub max_retries: Option<u32>,
+
+    #[arg(long, global = true, value_name = "MS")]
+    pub retry_delay_ms: Option<u64>,
+
+    #[arg(long, global = true, value_name = "SEGUNDOS")]
+    pub timeout_secs: Option<u64>,
+
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub stream: Option<bool>,
+
+    #[arg(long, global = true, value_name = "SEGUNDOS")]
+    pub idle_timeout_secs: Option<u64>,
+
+    #[arg(long, global = true, value_name = "N")]
+    pub max_chunks: Option<usize>,
+
+    #[arg(long, global = true, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    pub per_file: Option<bool>,
+
+    #[arg(long, global = true, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    pub structured: Option<bool>,
+
+    #[arg(long, global = true, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    #[arg(long, global = true, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    #[arg(long, global = true)]
+    pub no_default_excludes: bool,
+
+    #[arg(long, global = true, value_name = "NOME", value_parser = parse_profile)]
+    pub profile: Option<Profile>,
+
+    #[arg(long, global = true, value_name = "DIRETÓRIO")]
+    pub templates: Option<PathBuf>,
+
+    #[arg(long, global = true, value_name = "TEXTO")]
+    pub project_context: Option<String>,
+
+    #[arg(long, global = true, value_name = "IDIOMA", value_parser = parse_locale)]
+    pub locale: Option<Locale>,
+
+    #[arg(short = 'j', long, global = true, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    #[arg(long, global = true, value_name = "N")]
+    pub max_requests_per_host: Option<usize>,
+}
+
+#[derive(Args, Debug, Default, Clone)]
+pub struct SelectionArgs {
+    #[arg(long, value_name = "DATA")]
+    pub since: Option<String>,
+
+    #[arg(long, value_name = "DATA")]
+    pub until: Option<String>,
+
+    #[arg(long, value_name = "PADRÃO")]
+    pub author: Vec<String>,
+
+    #[arg(long, value_name = "PADRÃO")]
+    pub grep: Vec<String>,
+
+    #[arg(long)]
+    pub first_parent: bool,
+
+    #[arg(long)]
+    pub no_merges: bool,
+
+    #[arg(last = true, value_name = "CAMINHO")]
+    pub paths: Vec<String>,
+}
+
+impl SelectionArgs {
+    pub fn to_query(&self, range: Option<&str>, max_count: Option<u32>) -> CommitQuery {
+        CommitQuery {
+            range: range.map(String::from),
+            max_count,
+            since: self.since.clone(),
+            until: self.until.clone(),
+            authors: self.author.clone(),
+            grep: self.grep.clone(),
+            paths: self.paths.clone(),
+            first_parent: self.first_parent,
+            no_merges: self.no_merges,
+        }
+    }
+
+    pub fn has_date_filter(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+}
+
+impl SettingsArgs {
+    pub fn to_partial(&self) -> PartialSettings {
+        PartialSettings {
+            repo: self.repo.clone(),
+            backend: self.backend,
+            model: self.model.clone(),
+            ollama_url: self.ollama_url.clone(),
+            api_key: self.api_key.clone(),
+            max_diff_size: self.max_diff_size,
+            chunk_size: self.chunk_size,
+            num_ctx: self.num_ctx,
+            answer_tokens: self.answer_tokens,
+            max_retries: self.max_retries,
+            retry_delay_ms: self.retry_delay_ms,
+            timeout_secs: self.timeout_secs,
+            stream: self.stream,
+            idle_timeout_secs: self.idle_timeout_secs,
+            max_chunks: self.max_chunks,
+            per_file: self.per_file,
+            structured: self.structured,
+            include: (!self.include.is_empty()).then(|| self.include.clone()),
+            exclude: (!self.exclude.is_empty()).then(|| self.exclude.clone()),
+            default_excludes: self.no_default_excludes.then_some(false),
+            profile: self.profile,
+            templates: self.templates.clone(),
+            project_context: self.project_context.clone(),
+            locale: self.locale,
+            jobs: self.jobs,
+            max_requests_per_host: self.max_requests_per_host,
+        }
+    }
+}
+
+fn parse_backend(value: &str) -> Result<BackendKind, String> {
+    value.parse()
+}
+
+fn parse_profile(value: &str) -> Result<Profile, String> {
+    value.parse()
+}
+
+fn parse_locale(value: &str) -> Result<Locale, String> {
+    value.parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // percorre todos os subcomandos com o caminho usado no catalogo
+    fn visit(command: &Command, path: &str, f: &mut dyn FnMut(&Command, &str)) {
+        f(command, path);
+        for sub in command.get_subcommands() {
+            visit(sub, &join_path(path, sub.get_name()), f);
+        }
+    }
+
+    #[test]
+    fn every_argument_and_subcommand_has_help_in_every_locale() {
+        for locale in Locale::ALL {
+            let m = locale.messages();
+            let command = localized_command(m);
+            command.clone().debug_assert();
+
+            let mut gaps = Vec::new();
+            visit(&command, "", &mut |command, path| {
+                if !path.is_empty() && command.get_about().is_none() {
+                    gaps.push(path.to_string());
+                }
+                let args = command.get_arguments().filter(|a| !a.is_hide_set() && a.get_help().is_none());
+                gaps.extend(args.map(|a| join_path(path, a.get_id().as_str())));
+            });
+            // os nomes do catalogo sao os de antes da traducao
+            visit(&Cli::command(), "", &mut |command, _| {
+                // flags sem valor tambem tem nome de valor, que nunca aparece
+                let args = command.get_arguments().filter(|a| !a.is_hide_set() && a.get_action().takes_values());
+                let names = args.flat_map(|a| a.get_value_names().unwrap_or_default());
+                gaps.extend(names.filter(|n| value_name(m, n.as_str()).is_none()).map(|n| n.to_string()));
+            });
+            assert!(gaps.is_empty(), "{}: {:?}", locale, gaps);
+        }
+    }
+
+    #[test]
+    fn help_follows_the_catalog() {
+        let mut command = localized_command(Locale::En.messages());
+        let help = command.find_subcommand_mut("staged").unwrap().render_help().to_string();
+        assert!(help.starts_with("Analyzes the staged"), "{}", help);
+        assert!(help.contains("--message <TEXT>"), "{}", help);
+        assert!(help.contains("Saves the full report to this file"), "{}", help);
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn early_settings_reads_repo_and_locale_in_both_forms() {
+        let partial = early_settings(&args(&["bin", "--locale", "en", "analyze", "--repo=/tmp/x"]));
+        assert_eq!(partial.locale, Some(Locale::En));
+        assert_eq!(partial.repo, Some(PathBuf::from("/tmp/x")));
+
+        let partial = early_settings(&args(&["bin", "--locale=xx", "--model", "m"]));
+        assert_eq!(partial.locale, None);
+        assert_eq!(partial.model, None);
+
+        // depois de `--` sao caminhos
+        let partial = early_settings(&args(&["bin", "analyze", "--", "--locale", "en"]));
+        assert_eq!(partial.locale, None);
+    }
+}
@@ -0,0 +1,763 @@
+use crate::i18n::{msg, Locale};
+use crate::profile::Profile;
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// valores padrao
+pub const DEFAULT_REPO_PATH: &str = "."; // caminho do repo
+pub const DEFAULT_OLLAMA_MODEL: &str = "gemma3:4b-it-q8_0"; // modelo pra usar
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434"; // servidor do modelo
+pub const DEFAULT_NUM_CTX: usize = 8192; // janela de contexto quando o modelo nao informa (ou informa uma maior)
+pub const DEFAULT_ANSWER_TOKENS: usize = 1024; // tokens reservados pra resposta
+pub const DEFAULT_MAX_RETRIES: u32 = 3; // tentativas para Ollama
+pub const DEFAULT_RETRY_DELAY_MS: u64 = 1000; // delay entre tentativas
+pub const DEFAULT_OLLAMA_TIMEOUT_SECS: u64 = 600; // timeout para Ollama em segundos
+pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 120; // tempo maximo sem receber token no streaming
+pub const DEFAULT_OLLAMA_MAX_CHUNKS: usize = 100; // maximo de pedacos para dividir o diff
+pub const DEFAULT_JOBS: usize = 4; // commits processados ao mesmo tempo
+pub const DEFAULT_MAX_REQUESTS_PER_HOST: usize = 1; // requisicoes simultaneas ao mesmo servidor
+
+pub const REPO_CONFIG_FILE: &str = ".git-diff-extractor.toml";
+const USER_CONFIG_DIR: &str = "git-diff-extractor";
+const USER_CONFIG_FILE: &str = "config.toml";
+const ENV_PREFIX: &str = "GIT_DIFF_EXTRACTOR_";
+const OLLAMA_DEFAULT_PORT: u16 = 11434;
+
+// chaves na ordem em que aparecem no `config show`
+pub const KEYS: [&str; 26] = [
+    "repo",
+    "backend",
+    "model",
+    "ollama_url",
+    "api_key",
+    "max_diff_size",
+    "chunk_size",
+    "num_ctx",
+    "answer_tokens",
+    "max_retries",
+    "retry_delay_ms",
+    "timeout_secs",
+    "stream",
+    "idle_timeout_secs",
+    "max_chunks",
+    "per_file",
+    "structured",
+    "include",
+    "exclude",
+    "default_excludes",
+    "profile",
+    "templates",
+    "project_context",
+    "locale",
+    "jobs",
+    "max_requests_per_host",
+];
+
+// servidores de inferencia suportados
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackendKind {
+    Ollama,
+    OllamaChat,
+    Openai,
+    Echo,
+}
+
+impl BackendKind {
+    pub const ALL: [BackendKind; 4] = [BackendKind::Ollama, BackendKind::OllamaChat, BackendKind::Openai, BackendKind::Echo];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackendKind::Ollama => "ollama",
+            BackendKind::OllamaChat => "ollama-chat",
+            BackendKind::Openai => "openai",
+            BackendKind::Echo => "echo",
+        }
+    }
+}
+
+impl fmt::Display for BackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for BackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BackendKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = BackendKind::ALL.iter().map(|k| k.name()).collect();
+                (msg().unknown_backend)(&s, &names.join(", "))
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub repo_path: PathBuf,
+    pub backend: BackendKind,
+    pub model: String,
+    pub ollama_url: String,
+    pub api_key: Option<String>,
+    /// Limite em tokens do diff enviado de uma vez; `None` usa o que cabe na janela.
+    pub max_diff_size: Option<usize>,
+    /// Limite em tokens de cada pedaço; `None` usa o que cabe na janela.
+    pub chunk_size: Option<usize>,
+    /// Janela de contexto; `None` pergunta ao modelo.
+    pub num_ctx: Option<usize>,
+    pub answer_tokens: usize,
+    pub max_retries: u32,
+    pub retry_delay_ms: u64,
+    pub timeout_secs: u64,
+    pub stream: bool,
+    pub idle_timeout_secs: u64,
+    pub max_chunks: usize,
+    /// Uma análise por arquivo e uma síntese do commit, em vez de uma análise do diff inteiro.
+    pub per_file: bool,
+    /// Pede a resposta final como JSON (veja [`crate::analysis::Analysis`]).
+    pub structured: bool,
+    /// Quando não vazio, só arquivos que batem com algum destes globs vão para o modelo.
+    pub include: Vec<String>,
+    /// Globs de arquivos listados no relatório mas não enviados, além dos padrões embutidos.
+    pub exclude: Vec<String>,
+    /// Usa [`crate::filter::DEFAULT_EXCLUDES`].
+    pub default_excludes: bool,
+    /// Perfil da análise: papel do modelo, seções da resposta e formato do relatório.
+    pub profile: Profile,
+    /// Diretório com templates de prompt que substituem os embutidos.
+    pub templates: Option<PathBuf>,
+    /// Descrição do projeto disponível aos templates como `{{project_context}}`;
+    /// `None` usa a do perfil.
+    pub project_context: Option<String>,
+    /// Idioma do terminal, do relatório e das respostas do modelo.
+    pub locale: Locale,
+    pub jobs: usize,
+    pub max_requests_per_host: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            repo_path: PathBuf::from(DEFAULT_REPO_PATH),
+            backend: BackendKind::Ollama,
+            model: DEFAULT_OLLAMA_MODEL.to_string(),
+            ollama_url: DEFAULT_OLLAMA_URL.to_string(),
+            api_key: None,
+            max_diff_size: None,
+            chunk_size: None,
+            num_ctx: None,
+            answer_tokens: DEFAULT_ANSWER_TOKENS,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_delay_ms: DEFAULT_RETRY_DELAY_MS,
+            timeout_secs: DEFAULT_OLLAMA_TIMEOUT_SECS,
+            stream: true,
+            idle_timeout_secs: DEFAULT_IDLE_TIMEOUT_SECS,
+            max_chunks: DEFAULT_OLLAMA_MAX_CHUNKS,
+            per_file: false,
+            structured: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            default_excludes: true,
+            profile: Profile::Migration,
+            templates: None,
+            project_context: None,
+            locale: Locale::PtBr,
+            jobs: DEFAULT_JOBS,
+            max_requests_per_host: DEFAULT_MAX_REQUESTS_PER_HOST,
+        }
+    }
+}
+
+// uma camada de configuracao: arquivo, variaveis de ambiente ou flags
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialSettings {
+    pub repo: Option<PathBuf>,
+    pub backend: Option<BackendKind>,
+    pub model: Option<String>,
+    pub ollama_url: Option<String>,
+    pub api_key: Option<String>,
+    pub max_diff_size: Option<usize>,
+    pub chunk_size: Option<usize>,
+    pub num_ctx: Option<usize>,
+    pub answer_tokens: Option<usize>,
+    pub max_retries: Option<u32>,
+    pub retry_delay_ms: Option<u64>,
+    pub timeout_secs: Option<u64>,
+    pub stream: Option<bool>,
+    pub idle_timeout_secs: Option<u64>,
+    pub max_chunks: Option<usize>,
+    pub per_file: Option<bool>,
+    pub structured: Option<bool>,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub default_excludes: Option<bool>,
+    pub profile: Option<Profile>,
+    pub templates: Option<PathBuf>,
+    pub project_context: Option<String>,
+    pub locale: Option<Locale>,
+    pub jobs: Option<usize>,
+    pub max_requests_per_host: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Source {
+    Default,
+    File(PathBuf),
+    Env(String),
+    Cli,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let m = msg();
+        match self {
+            Source::Default => write!(f, "{}", m.source_default),
+            Source::File(path) => write!(f, "{}", (m.source_file)(&path.display())),
+            Source::Env(var) => write!(f, "{}", (m.source_env)(var)),
+            Source::Cli => write!(f, "{}", m.source_cli),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadedSettings {
+    pub settings: Settings,
+    pub sources: Vec<(&'static str, Source)>,
+    pub files: Vec<PathBuf>,
+}
+
+impl LoadedSettings {
+    fn new() -> Self {
+        LoadedSettings {
+            settings: Settings::default(),
+            sources: KEYS.iter().map(|k| (*k, Source::Default)).collect(),
+            files: Vec::new(),
+        }
+    }
+
+    fn mark(&mut self, key: &'static str, source: &Source) {
+        if let Some(entry) = self.sources.iter_mut().find(|(k, _)| *k == key) {
+            entry.1 = source.clone();
+        }
+    }
+
+    pub fn source_of(&self, key: &str) -> &Source {
+        self.sources
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, s)| s)
+            .unwrap_or(&Source::Default)
+    }
+
+    // aplica uma camada por cima das anteriores
+    pub fn apply(&mut self, layer: &PartialSettings, source: Source) {
+        let s = &mut self.settings;
+        let mut changed = Vec::new();
+
+        if let Some(v) = &layer.repo {
+            s.repo_path = v.clone();
+            changed.push("repo");
+        }
+        if let Some(v) = layer.backend {
+            s.backend = v;
+            changed.push("backend");
+        }
+        if let Some(v) = &layer.model {
+            s.model = v.clone();
+            changed.push("model");
+        }
+        if let Some(v) = &layer.ollama_url {
+            s.ollama_url = v.clone();
+            changed.push("ollama_url");
+        }
+        if let Some(v) = &layer.api_key {
+            s.api_key = Some(v.clone());
+            changed.push("api_key");
+        }
+        if let Some(v) = layer.max_diff_size {
+            s.max_diff_size = Some(v);
+            changed.push("max_diff_size");
+        }
+        if let Some(v) = layer.chunk_size {
+            s.chunk_size = Some(v);
+            changed.push("chunk_size");
+        }
+        if let Some(v) = layer.num_ctx {
+            s.num_ctx = Some(v);
+            changed.push("num_ctx");
+        }
+        if let Some(v) = layer.answer_tokens {
+            s.answer_tokens = v;
+            changed.push("answer_tokens");
+        }
+        if let Some(v) = layer.max_retries {
+            s.max_retries = v;
+            changed.push("max_retries");
+        }
+        if let Some(v) = layer.retry_delay_ms {
+            s.retry_delay_ms = v;
+            changed.push("retry_delay_ms");
+        }
+        if let Some(v) = layer.timeout_secs {
+            s.timeout_secs = v;
+            changed.push("timeout_secs");
+        }
+        if let Some(v) = layer.stream {
+            s.stream = v;
+            changed.push("stream");
+        }
+        if let Some(v) = layer.idle_timeout_secs {
+            s.idle_timeout_secs = v;
+            changed.push("idle_timeout_secs");
+        }
+        if let Some(v) = layer.max_chunks {
+            s.max_chunks = v;
+            changed.push("max_chunks");
+        }
+        if let Some(v) = layer.per_file {
+            s.per_file = v;
+            changed.push("per_file");
+        }
+        if let Some(v) = layer.structured {
+            s.structured = v;
+            changed.push("structured");
+        }
+        if let Some(v) = &layer.include {
+            s.include = v.clone();
+            changed.push("include");
+        }
+        if let Some(v) = &layer.exclude {
+            s.exclude = v.clone();
+            changed.push("exclude");
+        }
+        if let Some(v) = layer.default_excludes {
+            s.default_excludes = v;
+            changed.push("default_excludes");
+        }
+        if let Some(v) = layer.profile {
+            s.profile = v;
+            changed.push("profile");
+        }
+        if let Some(v) = &layer.templates {
+            s.templates = Some(v.clone());
+            changed.push("templates");
+        }
+        if let Some(v) = &layer.project_context {
+            s.project_context = Some(v.clone());
+            changed.push("project_context");
+        }
+        if let Some(v) = layer.locale {
+            s.locale = v;
+            changed.push("locale");
+        }
+        if let Some(v) = layer.jobs {
+            s.jobs = v;
+            changed.push("jobs");
+        }
+        if let Some(v) = layer.max_requests_per_host {
+            s.max_requests_per_host = v;
+            changed.push("max_requests_per_host");
+        }
+
+        for key in changed {
+            self.mark(key, &source);
+        }
+    }
+}
+
+// variaveis de ambiente lidas pelo load; nos testes vem de um mapa
+type EnvLookup<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+fn process_env(var: &str) -> Option<String> {
+    env::var(var).ok()
+}
+
+// ordem: padrao < usuario < repositorio < ambiente < linha de comando
+pub fn load(cli: &PartialSettings) -> Result<LoadedSettings, Box<dyn std::error::Error>> {
+    load_with_env(cli, &process_env)
+}
+
+fn load_with_env(cli: &PartialSettings, env: EnvLookup) -> Result<LoadedSettings, Box<dyn std::error::Error>> {
+    let mut loaded = LoadedSettings::new();
+    let env_layers = env_layers(env)?;
+
+    if let Some(path) = config_path_in(env).filter(|p| p.is_file()) {
+        let layer = read_config_file(&path)?;
+        loaded.apply(&layer, Source::File(path.clone()));
+        loaded.files.push(path);
+    }
+
+    // o arquivo do repo depende do caminho vindo do usuario, ambiente ou flag
+    let repo_path = cli
+        .repo
+        .clone()
+        .or_else(|| env_layers.iter().rev().find_map(|(l, _)| l.repo.clone()))
+        .unwrap_or_else(|| loaded.settings.repo_path.clone());
+    let repo_file = repo_root(&repo_path).join(REPO_CONFIG_FILE);
+    if repo_file.is_file() {
+        let mut layer = read_config_file(&repo_file)?;
+        // o arquivo do repo nao pode apontar pra outro repo
+        layer.repo = None;
+        loaded.apply(&layer, Source::File(repo_file.clone()));
+        loaded.files.push(repo_file);
+    }
+
+    for (layer, source) in env_layers {
+        loaded.apply(&layer, source);
+    }
+
+    loaded.apply(cli, Source::Cli);
+
+    Ok(loaded)
+}
+
+pub fn user_config_path() -> Option<PathBuf> {
+    config_path_in(&process_env)
+}
+
+fn config_path_in(env: EnvLookup) -> Option<PathBuf> {
+    let base = env("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env("APPDATA").map(PathBuf::from))
+        .or_else(|| env("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join(USER_CONFIG_DIR).join(USER_CONFIG_FILE))
+}
+
+fn repo_root(repo_path: &Path) -> PathBuf {
+    Command::new("git")
+        .arg("rev-parse")
+        .arg("--show-toplevel")
+        .current_dir(repo_path)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| PathBuf::from(s.trim()))
+        .unwrap_or_else(|| repo_path.to_path_buf())
+}
+
+fn read_config_file(path: &Path) -> Result<PartialSettings, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| (msg().read_failed)(&path.display(), &e))?;
+    let mut layer: PartialSettings =
+        toml::from_str(&content).map_err(|e| (msg().invalid_config)(&path.display(), &e))?;
+
+    // diretorio de templates relativo ao proprio arquivo de configuracao
+    if let Some(dir) = &layer.templates
+        && dir.is_relative()
+        && let Some(parent) = path.parent()
+    {
+        layer.templates = Some(parent.join(dir));
+    }
+    Ok(layer)
+}
+
+// uma camada por variavel definida, pra saber a origem de cada valor
+fn env_layers(env: EnvLookup) -> Result<Vec<(PartialSettings, Source)>, Box<dyn std::error::Error>> {
+    let mut layers = Vec::new();
+
+    if let Some(host) = env_value(env, "OLLAMA_HOST") {
+        let layer = PartialSettings {
+            ollama_url: Some(normalize_ollama_host(&host)),
+            ..Default::default()
+        };
+        layers.push((layer, Source::Env("OLLAMA_HOST".to_string())));
+    }
+
+    if let Some(key) = env_value(env, "OPENAI_API_KEY") {
+        let layer = PartialSettings {
+            api_key: Some(key),
+            ..Default::default()
+        };
+        layers.push((layer, Source::Env("OPENAI_API_KEY".to_string())));
+    }
+
+    for key in KEYS {
+        let var = format!("{}{}", ENV_PREFIX, key.to_uppercase());
+        let Some(value) = env_value(env, &var) else {
+            continue;
+        };
+
+        let invalid = |e: &dyn fmt::Display| (msg().invalid_env)(&var, &value, e);
+        let mut layer = PartialSettings::default();
+        match key {
+            "repo" => layer.repo = Some(PathBuf::from(&value)),
+            "backend" => layer.backend = Some(value.parse().map_err(|e| invalid(&e))?),
+            "model" => layer.model = Some(value.clone()),
+            "ollama_url" => layer.ollama_url = Some(value.clone()),
+            "api_key" => layer.api_key = Some(value.clone()),
+            "max_diff_size" => layer.max_diff_size = Some(value.parse().map_err(|e| invalid(&e))?),
+            "chunk_size" => layer.chunk_size = Some(value.parse().map_err(|e| invalid(&e))?),
+            "num_ctx" => layer.num_ctx = Some(value.parse().map_err(|e| invalid(&e))?),
+            "answer_tokens" => layer.answer_tokens = Some(value.parse().map_err(|e| invalid(&e))?),
+            "max_retries" => layer.max_retries = Some(value.parse().map_err(|e| invalid(&e))?),
+            "retry_delay_ms" => layer.retry_delay_ms = Some(value.parse().map_err(|e| invalid(&e))?),
+            "timeout_secs" => layer.timeout_secs = Some(value.parse().map_err(|e| invalid(&e))?),
+            "stream" => layer.stream = Some(value.parse().map_err(|e| invalid(&e))?),
+            "idle_timeout_secs" => layer.idle_timeout_secs = Some(value.parse().map_err(|e| invalid(&e))?),
+            "max_chunks" => layer.max_chunks = Some(value.parse().map_err(|e| invalid(&e))?),
+            "per_file" => layer.per_file = Some(value.parse().map_err(|e| invalid(&e))?),
+            "structured" => layer.structured = Some(value.parse().map_err(|e| invalid(&e))?),
+            "include" => layer.include = Some(split_list(&value)),
+            "exclude" => layer.exclude = Some(split_list(&value)),
+            "default_excludes" => layer.default_excludes = Some(value.parse().map_err(|e| invalid(&e))?),
+            "profile" => layer.profile = Some(value.parse().map_err(|e| invalid(&e))?),
+            "templates" => layer.templates = Some(PathBuf::from(&value)),
+            "project_context" => layer.project_context = Some(value.clone()),
+            "locale" => layer.locale = Some(value.parse().map_err(|e| invalid(&e))?),
+            "jobs" => layer.jobs = Some(value.parse().map_err(|e| invalid(&e))?),
+            "max_requests_per_host" => layer.max_requests_per_host = Some(value.parse().map_err(|e| invalid(&e))?),
+            _ => unreachable!(),
+        }
+        layers.push((layer, Source::Env(var)));
+    }
+
+    Ok(layers)
+}
+
+// listas nas variaveis de ambiente sao separadas por virgula
+fn split_list(value: &str) -> Vec<String> {
+    value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(String::from).collect()
+}
+
+fn env_value(env: EnvLookup, var: &str) -> Option<String> {
+    env(var).map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+// OLLAMA_HOST aceita "host", "host:porta" ou uma URL completa
+fn normalize_ollama_host(host: &str) -> String {
+    let with_scheme = if host.contains("://") {
+        host.to_string()
+    } else {
+        format!("http://{}", host)
+    };
+
+    match reqwest::Url::parse(&with_scheme) {
+        Ok(mut url) => {
+            if url.port().is_none() && !host.contains("://") {
+                let _ = url.set_port(Some(OLLAMA_DEFAULT_PORT));
+            }
+            url.as_str().trim_end_matches('/').to_string()
+        }
+        Err(_) => with_scheme,
+    }
+}
+
+// limites em tokens sem valor sao calculados a partir da janela de contexto
+fn auto_or(value: Option<usize>) -> String {
+    value.map_or_else(|| "auto".to_string(), |v| v.to_string())
+}
+
+impl Settings {
+    pub fn value_of(&self, key: &str) -> String {
+        match key {
+            "repo" => self.repo_path.display().to_string(),
+            "backend" => self.backend.to_string(),
+            "model" => self.model.clone(),
+            "ollama_url" => self.ollama_url.clone(),
+            // nunca mostra a chave
+            "api_key" => match &self.api_key {
+                Some(_) => "********".to_string(),
+                None => String::new(),
+            },
+            "max_diff_size" => auto_or(self.max_diff_size),
+            "chunk_size" => auto_or(self.chunk_size),
+            "num_ctx" => auto_or(self.num_ctx),
+            "answer_tokens" => self.answer_tokens.to_string(),
+            "max_retries" => self.max_retries.to_string(),
+            "retry_delay_ms" => self.retry_delay_ms.to_string(),
+            "timeout_secs" => self.timeout_secs.to_string(),
+            "stream" => self.stream.to_string(),
+            "idle_timeout_secs" => self.idle_timeout_secs.to_string(),
+            "max_chunks" => self.max_chunks.to_string(),
+            "per_file" => self.per_file.to_string(),
+            "structured" => self.structured.to_string(),
+            "include" => self.include.join(", "),
+            "exclude" => self.exclude.join(", "),
+            "default_excludes" => self.default_excludes.to_string(),
+            "templates" => self.templates.as_ref().map(|p| p.display().to_string()).unwrap_or_default(),
+            "profile" => self.profile.to_string(),
+            "project_context" => self.project_context.clone().unwrap_or_else(|| self.profile.text(self.locale.messages()).default_context.to_string()),
+            "locale" => self.locale.to_string(),
+            "jobs" => self.jobs.to_string(),
+            "max_requests_per_host" => self.max_requests_per_host.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let m = self.locale.messages();
+        if self.model.trim().is_empty() {
+            return Err(m.empty_model.into());
+        }
+
+        match reqwest::Url::parse(&self.ollama_url) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+            Ok(url) => {
+                return Err((m.invalid_url_scheme)(&url.scheme()).into());
+            }
+            Err(e) => {
+                return Err((m.invalid_url)(&self.ollama_url, &e).into());
+            }
+        }
+
+        let positives = [
+            // os opcionais so sao checados quando definidos
+            ("max_diff_size", self.max_diff_size.map_or(1, |v| v as u64)),
+            ("chunk_size", self.chunk_size.map_or(1, |v| v as u64)),
+            ("num_ctx", self.num_ctx.map_or(1, |v| v as u64)),
+            ("answer_tokens", self.answer_tokens as u64),
+            ("max_retries", self.max_retries as u64),
+            ("timeout_secs", self.timeout_secs),
+            ("idle_timeout_secs", self.idle_timeout_secs),
+            ("max_chunks", self.max_chunks as u64),
+            ("jobs", self.jobs as u64),
+            ("max_requests_per_host", self.max_requests_per_host as u64),
+        ];
+        for (name, value) in positives {
+            if value == 0 {
+                return Err((m.must_be_positive)(&name).into());
+            }
+        }
+
+        if let Some(num_ctx) = self.num_ctx
+            && self.answer_tokens >= num_ctx
+        {
+            return Err((m.answer_tokens_too_large)(&self.answer_tokens, &num_ctx).into());
+        }
+
+        crate::filter::PathFilter::new(self)?;
+        crate::prompt::PromptTemplates::load(self)?;
+
+        if !self.repo_path.is_dir() {
+            return Err((m.repo_not_found)(&self.repo_path.display()).into());
+        }
+
+        let output = Command::new("git")
+            .arg("rev-parse")
+            .arg("--git-dir")
+            .current_dir(&self.repo_path)
+            .output()
+            .map_err(|e| (m.git_unavailable)(&e))?;
+        if !output.status.success() {
+            return Err((m.not_a_repo)(&self.repo_path.display()).into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{temp_repo, TempDir};
+    use std::collections::HashMap;
+
+    fn load_with(cli: &PartialSettings, vars: &[(&str, &str)]) -> Result<LoadedSettings, Box<dyn std::error::Error>> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        load_with_env(cli, &|var| vars.get(var).cloned())
+    }
+
+    #[test]
+    fn layers_apply_in_order() {
+        let home = TempDir::new();
+        let user_dir = home.path().join(USER_CONFIG_DIR);
+        std::fs::create_dir_all(&user_dir).unwrap();
+        std::fs::write(
+            user_dir.join(USER_CONFIG_FILE),
+            "model = \"usuario\"\nnum_ctx = 1000\njobs = 2\nanswer_tokens = 100\n",
+        )
+        .unwrap();
+        let repo = temp_repo();
+        std::fs::write(repo.path().join(REPO_CONFIG_FILE), "model = \"repo\"\nnum_ctx = 2000\njobs = 3\n").unwrap();
+
+        let cli = PartialSettings { model: Some("cli".to_string()), ..Default::default() };
+        let loaded = load_with(
+            &cli,
+            &[
+                ("XDG_CONFIG_HOME", &home.path().display().to_string()),
+                ("GIT_DIFF_EXTRACTOR_REPO", &repo.path().display().to_string()),
+                ("GIT_DIFF_EXTRACTOR_MODEL", "ambiente"),
+                ("GIT_DIFF_EXTRACTOR_JOBS", "5"),
+            ],
+        )
+        .unwrap();
+
+        let s = &loaded.settings;
+        assert_eq!(s.max_chunks, DEFAULT_OLLAMA_MAX_CHUNKS);
+        assert_eq!(s.answer_tokens, 100);
+        assert_eq!(s.num_ctx, Some(2000));
+        assert_eq!(s.jobs, 5);
+        assert_eq!(s.model, "cli");
+        assert_eq!(loaded.files.len(), 2);
+
+        assert!(matches!(loaded.source_of("max_chunks"), Source::Default));
+        assert!(matches!(loaded.source_of("answer_tokens"), Source::File(path) if path.starts_with(home.path())));
+        assert!(matches!(loaded.source_of("num_ctx"), Source::File(path) if path.starts_with(repo.path())));
+        assert!(matches!(loaded.source_of("jobs"), Source::Env(var) if var == "GIT_DIFF_EXTRACTOR_JOBS"));
+        assert!(matches!(loaded.source_of("model"), Source::Cli));
+    }
+
+    #[test]
+    fn repo_file_cannot_point_to_another_repo() {
+        let repo = temp_repo();
+        std::fs::write(repo.path().join(REPO_CONFIG_FILE), "repo = \"/outro\"\n").unwrap();
+
+        let cli = PartialSettings { repo: Some(repo.path().to_path_buf()), ..Default::default() };
+        let loaded = load_with(&cli, &[]).unwrap();
+        assert_eq!(loaded.settings.repo_path, repo.path());
+    }
+
+    #[test]
+    fn invalid_env_values_name_the_variable() {
+        let error = load_with(&PartialSettings::default(), &[("GIT_DIFF_EXTRACTOR_JOBS", "muitos")]).unwrap_err();
+        assert!(error.to_string().contains("GIT_DIFF_EXTRACTOR_JOBS"), "{}", error);
+
+        let error = load_with(&PartialSettings::default(), &[("GIT_DIFF_EXTRACTOR_BACKEND", "llama")]).unwrap_err();
+        assert!(error.to_string().contains("llama"), "{}", error);
+    }
+
+    #[test]
+    fn env_lists_and_blank_values() {
+        let loaded = load_with(
+            &PartialSettings::default(),
+            &[("GIT_DIFF_EXTRACTOR_EXCLUDE", " *.lock, ,docs/** "), ("GIT_DIFF_EXTRACTOR_MODEL", "  ")],
+        )
+        .unwrap();
+        assert_eq!(loaded.settings.exclude, vec!["*.lock", "docs/**"]);
+        assert_eq!(loaded.settings.model, DEFAULT_OLLAMA_MODEL);
+    }
+
+    #[test]
+    fn prefixed_url_wins_over_ollama_host() {
+        let loaded = load_with(&PartialSettings::default(), &[("OLLAMA_HOST", "gpu")]).unwrap();
+        assert_eq!(loaded.settings.ollama_url, "http://gpu:11434");
+
+        let loaded = load_with(
+            &PartialSettings::default(),
+            &[("OLLAMA_HOST", "gpu"), ("GIT_DIFF_EXTRACTOR_OLLAMA_URL", "http://outro:8080")],
+        )
+        .unwrap();
+        assert_eq!(loaded.settings.ollama_url, "http://outro:8080");
+    }
+
+    #[test]
+    fn ollama_host_forms() {
+        assert_eq!(normalize_ollama_host("gpu-server"), "http://gpu-server:11434");
+        assert_eq!(normalize_ollama_host("gpu-server:8080"), "http://gpu-server:8080");
+        assert_eq!(normalize_ollama_host("0.0.0.0"), "http://0.0.0.0:11434");
+        // com esquema a porta fica a da URL
+        assert_eq!(normalize_ollama_host("https://ollama.exemplo.com"), "https://ollama.exemplo.com");
+        assert_eq!(normalize_ollama_host("http://10.0.0.2:11434/"), "http://10.0.0.2:11434");
+    }
+}
@@ -0,0 +1,804 @@
+//! Modelo tipado de um diff unificado do git (`git show`, `git diff`).
+
+use crate::i18n::Messages;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+    Copied,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    pub content: String,
+    /// Número da linha no arquivo antigo (contexto e removidas).
+    pub old_line: Option<u32>,
+    /// Número da linha no arquivo novo (contexto e adicionadas).
+    pub new_line: Option<u32>,
+    /// Seguida de `\ No newline at end of file`.
+    pub no_newline_at_eof: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// Texto depois do `@@ ... @@`, normalmente a função ou classe.
+    pub context: String,
+    pub lines: Vec<DiffLine>,
+}
+
+impl Hunk {
+    pub fn header(&self) -> String {
+        let header = format!("@@ -{},{} +{},{} @@", self.old_start, self.old_lines, self.new_start, self.new_lines);
+        if self.context.is_empty() {
+            header
+        } else {
+            format!("{} {}", header, self.context)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDiff {
+    /// `None` quando o arquivo foi criado.
+    pub old_path: Option<String>,
+    /// `None` quando o arquivo foi removido.
+    pub new_path: Option<String>,
+    pub status: FileStatus,
+    pub old_mode: Option<String>,
+    pub new_mode: Option<String>,
+    /// Percentual de similaridade em renomeações e cópias.
+    pub similarity: Option<u8>,
+    pub binary: bool,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FileDiff {
+    fn new(old_path: Option<String>, new_path: Option<String>) -> Self {
+        FileDiff {
+            old_path,
+            new_path,
+            status: FileStatus::Modified,
+            old_mode: None,
+            new_mode: None,
+            similarity: None,
+            binary: false,
+            hunks: Vec::new(),
+        }
+    }
+
+    /// Caminho atual do arquivo (o antigo, se foi removido).
+    pub fn path(&self) -> &str {
+        self.new_path.as_deref().or(self.old_path.as_deref()).unwrap_or("")
+    }
+
+    pub fn mode_changed(&self) -> bool {
+        self.old_mode.is_some() && self.new_mode.is_some() && self.old_mode != self.new_mode
+    }
+
+    pub fn insertions(&self) -> u32 {
+        self.count(LineKind::Added)
+    }
+
+    pub fn deletions(&self) -> u32 {
+        self.count(LineKind::Removed)
+    }
+
+    fn count(&self, kind: LineKind) -> u32 {
+        self.hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .filter(|line| line.kind == kind)
+            .count() as u32
+    }
+
+    /// Descrição curta do que aconteceu com o arquivo além das linhas
+    /// alteradas (criação, remoção, renomeação, binário, modo). `None` numa
+    /// modificação comum de texto.
+    pub fn describe(&self, m: &Messages) -> Option<String> {
+        let mut parts = Vec::new();
+        match self.status {
+            FileStatus::Added => parts.push(match self.new_mode.as_deref().and_then(|mode| mode_kind(mode, m)) {
+                Some(kind) => format!("{} ({})", m.file_added, kind),
+                None => m.file_added.to_string(),
+            }),
+            FileStatus::Deleted => parts.push(m.file_deleted.to_string()),
+            FileStatus::Renamed | FileStatus::Copied => {
+                let old = self.old_path.as_deref().unwrap_or("");
+                let mut text = if self.status == FileStatus::Renamed { (m.file_renamed)(&old) } else { (m.file_copied)(&old) };
+                if let Some(similarity) = self.similarity {
+                    text.push_str(&format!(" ({})", (m.similarity)(&similarity)));
+                }
+                parts.push(text);
+            }
+            FileStatus::Modified => {}
+        }
+        if self.mode_changed() {
+            let (old, new) = (self.old_mode.as_deref().unwrap_or(""), self.new_mode.as_deref().unwrap_or(""));
+            let mut text = (m.mode_change)(&old, &new);
+            if let Some(kind) = mode_kind(new, m) {
+                text.push_str(&format!(" ({})", (m.mode_now)(&kind)));
+            }
+            parts.push(text);
+        }
+        if self.binary {
+            parts.push(m.file_binary.to_string());
+        }
+
+        let text = parts.join("; ");
+        let mut chars = text.chars();
+        chars.next().map(|first| first.to_uppercase().chain(chars).collect())
+    }
+
+    pub fn change(&self, m: &Messages) -> FileChange {
+        FileChange {
+            path: self.path().to_string(),
+            description: self.describe(m).unwrap_or_else(|| m.file_modified.to_string()),
+            binary: self.binary,
+            insertions: self.insertions(),
+            deletions: self.deletions(),
+        }
+    }
+}
+
+// so os modos que mudam o significado do arquivo; 100644 e o normal
+fn mode_kind<'m>(mode: &str, m: &'m Messages) -> Option<&'m str> {
+    match mode {
+        "100755" => Some(m.mode_executable),
+        "120000" => Some(m.mode_symlink),
+        "160000" => Some(m.mode_submodule),
+        _ => None,
+    }
+}
+
+/// Resumo de um arquivo do diff para a tabela do relatório.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    pub path: String,
+    pub description: String,
+    pub binary: bool,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiffStats {
+    pub files: Vec<String>,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
+impl DiffStats {
+    pub fn from_files(files: &[FileDiff]) -> Self {
+        DiffStats {
+            files: files.iter().map(|f| f.path().to_string()).collect(),
+            insertions: files.iter().map(FileDiff::insertions).sum(),
+            deletions: files.iter().map(FileDiff::deletions).sum(),
+        }
+    }
+
+    /// Lê a saída de `git diff --numstat -z`. Binários vêm com `-` nas contagens e
+    /// renomeações com o caminho antigo e o novo; fica o novo, como em [`FileDiff::path`].
+    pub fn from_numstat(text: &str) -> Self {
+        let mut stats = DiffStats { files: Vec::new(), insertions: 0, deletions: 0 };
+        let mut fields = text.split('\0');
+
+        while let Some(record) = fields.next() {
+            let mut columns = record.splitn(3, '\t');
+            let (Some(added), Some(deleted), Some(path)) = (columns.next(), columns.next(), columns.next()) else {
+                continue;
+            };
+            // com -z, o caminho vazio quer dizer renomeacao: antigo e novo vem nos campos seguintes
+            let path = if path.is_empty() { fields.nth(1).unwrap_or("") } else { path };
+
+            stats.files.push(path.to_string());
+            stats.insertions += added.parse::<u32>().unwrap_or(0);
+            stats.deletions += deleted.parse::<u32>().unwrap_or(0);
+        }
+        stats
+    }
+}
+
+/// Lê um diff unificado. O que vem antes do primeiro `diff --git` (cabeçalho do
+/// `git show`) é ignorado, assim como linhas que o parser não reconhece.
+pub fn parse_unified_diff(text: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    // linhas que ainda faltam no hunk atual (antigo, novo) e as colunas de prefixo
+    let mut remaining = (0u32, 0u32);
+    let mut parents = 1;
+    let mut next_line = (0u32, 0u32);
+
+    for line in text.lines() {
+        let in_hunk = remaining.0 > 0 || remaining.1 > 0;
+
+        if in_hunk && let Some(file) = files.last_mut() {
+            let Some(hunk) = file.hunks.last_mut() else { continue };
+            if line.starts_with('\\') {
+                // "\ No newline at end of file" vale pra linha anterior
+                if let Some(last) = hunk.lines.last_mut() {
+                    last.no_newline_at_eof = true;
+                }
+                continue;
+            }
+            if let Some(diff_line) = parse_hunk_line(line, parents, &mut next_line) {
+                match diff_line.kind {
+                    LineKind::Context => {
+                        remaining.0 = remaining.0.saturating_sub(1);
+                        remaining.1 = remaining.1.saturating_sub(1);
+                    }
+                    LineKind::Removed => remaining.0 = remaining.0.saturating_sub(1),
+                    LineKind::Added => remaining.1 = remaining.1.saturating_sub(1),
+                }
+                hunk.lines.push(diff_line);
+                continue;
+            }
+            // linha inesperada: o hunk acabou antes do que o cabecalho dizia
+            remaining = (0, 0);
+        }
+
+        if line.starts_with('\\') {
+            // "\ No newline at end of file" vale pra linha anterior
+            if let Some(last) = files.last_mut().and_then(|f| f.hunks.last_mut()).and_then(|h| h.lines.last_mut()) {
+                last.no_newline_at_eof = true;
+            }
+        } else if let Some(rest) = line.strip_prefix("diff --git ") {
+            let (old_path, new_path) = parse_git_header_paths(rest);
+            files.push(FileDiff::new(Some(old_path), Some(new_path)));
+            parents = 1;
+        } else if let Some(rest) = line.strip_prefix("diff --cc ").or_else(|| line.strip_prefix("diff --combined ")) {
+            let path = unquote(rest);
+            files.push(FileDiff::new(Some(path.clone()), Some(path)));
+        } else if let Some(file) = files.last_mut() {
+            if let Some(hunk) = parse_hunk_header(line) {
+                parents = line.chars().take_while(|&c| c == '@').count() - 1;
+                remaining = (hunk.old_lines, hunk.new_lines);
+                next_line = (hunk.old_start, hunk.new_start);
+                // hunks de merge nao tem contagem confiavel por pai, le ate a proxima linha estranha
+                if parents > 1 {
+                    remaining = (u32::MAX, u32::MAX);
+                }
+                file.hunks.push(hunk);
+            } else {
+                parse_extended_header(file, line);
+            }
+        }
+    }
+
+    mark_copies(&mut files);
+    files
+}
+
+// o libgit2 marca como renomeacao todo destino de um arquivo removido; no git so o
+// ultimo e renomeacao e os anteriores sao copias
+fn mark_copies(files: &mut [FileDiff]) {
+    for i in 0..files.len() {
+        if files[i].status != FileStatus::Renamed {
+            continue;
+        }
+        let source = files[i].old_path.clone();
+        if files[i + 1..].iter().any(|f| f.status == FileStatus::Renamed && f.old_path == source) {
+            files[i].status = FileStatus::Copied;
+        }
+    }
+}
+
+fn parse_extended_header(file: &mut FileDiff, line: &str) {
+    if let Some(mode) = line.strip_prefix("new file mode ") {
+        file.status = FileStatus::Added;
+        file.old_path = None;
+        file.new_mode = Some(mode.to_string());
+    } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+        file.status = FileStatus::Deleted;
+        file.new_path = None;
+        file.old_mode = Some(mode.to_string());
+    } else if let Some(mode) = line.strip_prefix("old mode ") {
+        file.old_mode = Some(mode.to_string());
+    } else if let Some(mode) = line.strip_prefix("new mode ") {
+        file.new_mode = Some(mode.to_string());
+    } else if let Some(value) = line.strip_prefix("similarity index ") {
+        file.similarity = value.trim_end_matches('%').parse().ok();
+    } else if let Some(path) = line.strip_prefix("rename from ") {
+        file.status = FileStatus::Renamed;
+        file.old_path = Some(unquote(path));
+    } else if let Some(path) = line.strip_prefix("rename to ") {
+        file.status = FileStatus::Renamed;
+        file.new_path = Some(unquote(path));
+    } else if let Some(path) = line.strip_prefix("copy from ") {
+        file.status = FileStatus::Copied;
+        file.old_path = Some(unquote(path));
+    } else if let Some(path) = line.strip_prefix("copy to ") {
+        file.status = FileStatus::Copied;
+        file.new_path = Some(unquote(path));
+    } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+        file.binary = true;
+    } else if let Some(path) = line.strip_prefix("--- ") {
+        // os caminhos do ---/+++ valem mais que os do `diff --git`, que podem ser ambiguos
+        file.old_path = strip_side_prefix(path, "a/");
+    } else if let Some(path) = line.strip_prefix("+++ ") {
+        file.new_path = strip_side_prefix(path, "b/");
+    }
+}
+
+fn strip_side_prefix(path: &str, prefix: &str) -> Option<String> {
+    // o git pode colocar um tab depois do nome quando ele tem espacos
+    let path = unquote(path.trim_end_matches('\t'));
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).map(String::from).unwrap_or(path))
+}
+
+// "@@ -1,5 +1,6 @@ fn main()" e a variante "@@@ -1 -1 +1 @@@" de merges
+fn parse_hunk_header(line: &str) -> Option<Hunk> {
+    let marker_len = line.chars().take_while(|&c| c == '@').count();
+    if marker_len < 2 {
+        return None;
+    }
+    let marker = &line[..marker_len];
+    let rest = line[marker_len..].strip_prefix(' ')?;
+    let end = rest.find(&format!(" {}", marker))?;
+
+    let ranges: Vec<&str> = rest[..end].split(' ').collect();
+    let old = ranges.first()?.strip_prefix('-')?;
+    let new = ranges.last()?.strip_prefix('+')?;
+    let (old_start, old_lines) = parse_range(old)?;
+    let (new_start, new_lines) = parse_range(new)?;
+
+    Some(Hunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        context: rest[end + marker_len + 1..].trim().to_string(),
+        lines: Vec::new(),
+    })
+}
+
+// "12,3" ou "12" (uma linha so)
+fn parse_range(range: &str) -> Option<(u32, u32)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+fn parse_hunk_line(line: &str, parents: usize, next_line: &mut (u32, u32)) -> Option<DiffLine> {
+    // linha vazia de contexto: alguns editores removem o espaco do inicio
+    let (prefix, content) = if line.is_empty() {
+        (" ".repeat(parents), "")
+    } else {
+        let split = line.char_indices().nth(parents).map_or(line.len(), |(i, _)| i);
+        (line[..split].to_string(), &line[split..])
+    };
+
+    if prefix.chars().count() != parents || !prefix.chars().all(|c| matches!(c, ' ' | '+' | '-')) {
+        return None;
+    }
+
+    let kind = if prefix.contains('+') {
+        LineKind::Added
+    } else if prefix.contains('-') {
+        LineKind::Removed
+    } else {
+        LineKind::Context
+    };
+
+    let (old_line, new_line) = match kind {
+        LineKind::Context => (Some(next_line.0), Some(next_line.1)),
+        LineKind::Removed => (Some(next_line.0), None),
+        LineKind::Added => (None, Some(next_line.1)),
+    };
+    if old_line.is_some() {
+        next_line.0 += 1;
+    }
+    if new_line.is_some() {
+        next_line.1 += 1;
+    }
+
+    Some(DiffLine {
+        kind,
+        content: content.to_string(),
+        old_line,
+        new_line,
+        no_newline_at_eof: false,
+    })
+}
+
+// "a/x b/x"; com espacos no nome o unico jeito seguro e quando os dois lados sao iguais
+fn parse_git_header_paths(rest: &str) -> (String, String) {
+    if rest.starts_with('"') {
+        let (old, new) = split_quoted(rest);
+        return (strip_ab(&old, "a/"), strip_ab(&new, "b/"));
+    }
+
+    let len = rest.len();
+    if len > 5 && (len - 1).is_multiple_of(2) {
+        let half = (len - 1) / 2;
+        if rest.is_char_boundary(half) {
+            let (old, new) = (&rest[..half], &rest[half + 1..]);
+            if old.get(2..) == new.get(2..) {
+                return (strip_ab(old, "a/"), strip_ab(new, "b/"));
+            }
+        }
+    }
+
+    match rest.rfind(" b/") {
+        Some(pos) => (strip_ab(&rest[..pos], "a/"), rest[pos + 3..].to_string()),
+        None => (rest.to_string(), rest.to_string()),
+    }
+}
+
+fn strip_ab(path: &str, prefix: &str) -> String {
+    let path = unquote(path);
+    path.strip_prefix(prefix).map(String::from).unwrap_or(path)
+}
+
+// "\"a/x y\" \"b/x y\"" ou "\"a/x\" b/x"
+fn split_quoted(rest: &str) -> (String, String) {
+    let mut escaped = false;
+    for (i, c) in rest.char_indices().skip(1) {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => {
+                let (old, new) = rest.split_at(i + 1);
+                return (old.to_string(), new.trim_start().to_string());
+            }
+            _ => escaped = false,
+        }
+    }
+    (rest.to_string(), rest.to_string())
+}
+
+/// Desfaz as aspas que o git usa em nomes com espaços, acentos ou caracteres de controle.
+pub fn unquote(path: &str) -> String {
+    let Some(inner) = path.strip_prefix('"').and_then(|p| p.strip_suffix('"')) else {
+        return path.to_string();
+    };
+
+    let mut bytes = Vec::new();
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('n') => bytes.push(b'\n'),
+            Some('t') => bytes.push(b'\t'),
+            Some('r') => bytes.push(b'\r'),
+            Some('a') => bytes.push(0x07),
+            Some('b') => bytes.push(0x08),
+            Some('f') => bytes.push(0x0c),
+            Some('v') => bytes.push(0x0b),
+            // octal: bytes de nomes em UTF-8
+            Some(d @ '0'..='7') => {
+                let mut value = d.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    if let Some(next) = chars.peek().and_then(|c| c.to_digit(8)) {
+                        value = value * 8 + next;
+                        chars.next();
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => {
+                let mut buf = [0; 4];
+                bytes.extend_from_slice(other.encode_utf8(&mut buf).as_bytes());
+            }
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Locale;
+
+    #[test]
+    fn ignores_git_show_header_and_reads_line_numbers() {
+        let diff = "\
+commit 1234567890abcdef
+Author: Fulano <f@x>
+
+    mensagem com - e + no inicio
+-   outra linha do corpo
+
+diff --git a/src/main.rs b/src/main.rs
+index 83db48f..bf269f4 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -10,4 +10,5 @@ fn main() {
+     let a = 1;
+-    let b = 2;
++    let b = 3;
++    let c = 4;
+     println!();
+ }
+";
+        let files = parse_unified_diff(diff);
+        assert_eq!(files.len(), 1);
+
+        let file = &files[0];
+        assert_eq!(file.status, FileStatus::Modified);
+        assert_eq!(file.path(), "src/main.rs");
+        assert_eq!((file.insertions(), file.deletions()), (2, 1));
+
+        let hunk = &file.hunks[0];
+        assert_eq!(hunk.context, "fn main() {");
+        assert_eq!(hunk.header(), "@@ -10,4 +10,5 @@ fn main() {");
+        assert_eq!(hunk.lines.len(), 6);
+        assert_eq!(hunk.lines[1].kind, LineKind::Removed);
+        assert_eq!(hunk.lines[1].old_line, Some(11));
+        assert_eq!(hunk.lines[3].new_line, Some(12));
+        assert_eq!((hunk.lines[5].old_line, hunk.lines[5].new_line), (Some(13), Some(14)));
+    }
+
+    #[test]
+    fn content_that_looks_like_headers_stays_in_the_hunk() {
+        let diff = "\
+diff --git a/notes.md b/notes.md
+--- a/notes.md
++++ b/notes.md
+@@ -1,2 +1,3 @@
+---- a/old
+++++ b/new
++diff --git a/x b/x
+ @@ not a header
+";
+        let files = parse_unified_diff(diff);
+        assert_eq!(files.len(), 1);
+        let lines = &files[0].hunks[0].lines;
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0].kind, LineKind::Removed);
+        assert_eq!(lines[0].content, "--- a/old");
+        assert_eq!(lines[1].content, "+++ b/new");
+        assert_eq!(lines[2].content, "diff --git a/x b/x");
+        assert_eq!(lines[3].kind, LineKind::Context);
+    }
+
+    #[test]
+    fn rename_with_similarity_and_mode_change() {
+        let diff = "\
+diff --git a/old name.txt b/new name.txt
+old mode 100644
+new mode 100755
+similarity index 92%
+rename from old name.txt
+rename to new name.txt
+index 1111111..2222222
+--- a/old name.txt
++++ b/new name.txt
+@@ -3 +3 @@
+-x
++y
+";
+        let file = &parse_unified_diff(diff)[0];
+        assert_eq!(file.status, FileStatus::Renamed);
+        assert_eq!(file.old_path.as_deref(), Some("old name.txt"));
+        assert_eq!(file.new_path.as_deref(), Some("new name.txt"));
+        assert_eq!(file.similarity, Some(92));
+        assert!(file.mode_changed());
+        assert_eq!(file.new_mode.as_deref(), Some("100755"));
+        assert_eq!((file.hunks[0].old_lines, file.hunks[0].new_lines), (1, 1));
+    }
+
+    #[test]
+    fn pure_rename_and_mode_only_have_no_hunks() {
+        let diff = "\
+diff --git a/a.txt b/b.txt
+similarity index 100%
+rename from a.txt
+rename to b.txt
+diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100755
+";
+        let files = parse_unified_diff(diff);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].status, FileStatus::Renamed);
+        assert_eq!(files[0].path(), "b.txt");
+        assert!(files[0].hunks.is_empty());
+        assert_eq!(files[1].status, FileStatus::Modified);
+        assert!(files[1].mode_changed());
+    }
+
+    #[test]
+    fn describes_changes_without_line_counts() {
+        let diff = "\
+diff --git a/a.txt b/b.txt
+similarity index 100%
+rename from a.txt
+rename to b.txt
+diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100755
+diff --git a/base.rs b/copia.rs
+similarity index 90%
+copy from base.rs
+copy to copia.rs
+diff --git a/logo.png b/logo.png
+new file mode 100644
+index 0000000..8b2c1d0
+Binary files /dev/null and b/logo.png differ
+diff --git a/main.rs b/main.rs
+index ce01362..3f4e2a1 100644
+--- a/main.rs
++++ b/main.rs
+@@ -1 +1 @@
+-a
++b
+";
+        let m = Locale::PtBr.messages();
+        let files = parse_unified_diff(diff);
+        let descriptions: Vec<_> = files.iter().map(|file| file.describe(m)).collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                Some("Renomeado de `a.txt` (similaridade 100%)".to_string()),
+                Some("Modo 100644 → 100755 (agora executável)".to_string()),
+                Some("Copiado de `base.rs` (similaridade 90%)".to_string()),
+                Some("Arquivo novo; binário, conteúdo não exibido".to_string()),
+                None,
+            ]
+        );
+        assert_eq!(files[4].change(m).description, "modificado");
+    }
+
+    #[test]
+    fn added_deleted_and_binary_files() {
+        let diff = "\
+diff --git a/novo.txt b/novo.txt
+new file mode 100644
+index 0000000..ce01362
+--- /dev/null
++++ b/novo.txt
+@@ -0,0 +1 @@
++hello
+diff --git a/velho.txt b/velho.txt
+deleted file mode 100644
+index ce01362..0000000
+--- a/velho.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-hello
+diff --git a/logo.png b/logo.png
+index 3f4e2a1..8b2c1d0 100644
+Binary files a/logo.png and b/logo.png differ
+";
+        let files = parse_unified_diff(diff);
+        assert_eq!(files.len(), 3);
+
+        assert_eq!(files[0].status, FileStatus::Added);
+        assert_eq!(files[0].old_path, None);
+        assert_eq!(files[0].path(), "novo.txt");
+        assert_eq!(files[0].hunks[0].lines[0].new_line, Some(1));
+
+        assert_eq!(files[1].status, FileStatus::Deleted);
+        assert_eq!(files[1].new_path, None);
+        assert_eq!(files[1].path(), "velho.txt");
+        assert_eq!(files[1].deletions(), 1);
+
+        assert!(files[2].binary);
+        assert!(files[2].hunks.is_empty());
+
+        let stats = DiffStats::from_files(&files);
+        assert_eq!(stats.files, vec!["novo.txt", "velho.txt", "logo.png"]);
+        assert_eq!((stats.insertions, stats.deletions), (1, 1));
+    }
+
+    #[test]
+    fn numstat_with_renames_and_binaries() {
+        let numstat = ["3\t1\tsrc/a.rs", "-\t-\tlogo.png", "0\t2\t", "velho.txt", "novo.txt", ""].join("\0");
+        let stats = DiffStats::from_numstat(&numstat);
+        assert_eq!(stats.files, vec!["src/a.rs", "logo.png", "novo.txt"]);
+        assert_eq!((stats.insertions, stats.deletions), (3, 3));
+    }
+
+    #[test]
+    fn no_newline_at_end_of_file_marks_the_previous_line() {
+        let diff = "\
+diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1 @@
+-old
+\\ No newline at end of file
++new
+\\ No newline at end of file
+";
+        let lines = &parse_unified_diff(diff)[0].hunks[0].lines;
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.no_newline_at_eof));
+    }
+
+    #[test]
+    fn quoted_paths_with_unicode_and_spaces() {
+        let diff = "\
+diff --git \"a/docs/caf\\303\\251 \\\"x\\\".md\" \"b/docs/caf\\303\\251 \\\"x\\\".md\"
+new file mode 100644
+--- /dev/null
++++ \"b/docs/caf\\303\\251 \\\"x\\\".md\"
+@@ -0,0 +1 @@
++oi
+";
+        let file = &parse_unified_diff(diff)[0];
+        assert_eq!(file.path(), "docs/café \"x\".md");
+        assert_eq!(unquote("\"tab\\there\""), "tab\there");
+        assert_eq!(unquote("sem aspas"), "sem aspas");
+    }
+
+    #[test]
+    fn header_paths_with_spaces_and_b_slash() {
+        assert_eq!(parse_git_header_paths("a/x b/y.txt b/x b/y.txt"), ("x b/y.txt".to_string(), "x b/y.txt".to_string()));
+        assert_eq!(parse_git_header_paths("a/old.txt b/new.txt"), ("old.txt".to_string(), "new.txt".to_string()));
+    }
+
+    #[test]
+    fn multiple_hunks_and_single_line_ranges() {
+        let diff = "\
+diff --git a/lib.rs b/lib.rs
+--- a/lib.rs
++++ b/lib.rs
+@@ -1 +1,2 @@
+ use std::io;
++use std::fs;
+@@ -40,3 +41,2 @@ impl Foo {
+     fn a() {}
+-    fn b() {}
+     fn c() {}
+";
+        let file = &parse_unified_diff(diff)[0];
+        assert_eq!(file.hunks.len(), 2);
+        assert_eq!((file.hunks[0].old_start, file.hunks[0].old_lines), (1, 1));
+        assert_eq!(file.hunks[1].context, "impl Foo {");
+        assert_eq!(file.hunks[1].lines[1].old_line, Some(41));
+        assert_eq!(file.hunks[1].lines[2].new_line, Some(42));
+    }
+
+    #[test]
+    fn combined_diff_of_a_merge() {
+        let diff = "\
+diff --cc src/app.rs
+index 1111111,2222222..3333333
+--- a/src/app.rs
++++ b/src/app.rs
+@@@ -1,3 -1,3 +1,4 @@@ mod app
+  comum
+- removida do primeiro
+ +adicionada do segundo
+++resolvida no merge
+";
+        let file = &parse_unified_diff(diff)[0];
+        assert_eq!(file.path(), "src/app.rs");
+        let hunk = &file.hunks[0];
+        assert_eq!(hunk.context, "mod app");
+        assert_eq!(hunk.lines.len(), 4);
+        assert_eq!(hunk.lines[0].kind, LineKind::Context);
+        assert_eq!(hunk.lines[1].kind, LineKind::Removed);
+        assert_eq!(hunk.lines[2].kind, LineKind::Added);
+        assert_eq!(hunk.lines[3].content, "resolvida no merge");
+    }
+
+    #[test]
+    fn empty_input_and_garbage() {
+        assert!(parse_unified_diff("").is_empty());
+        assert!(parse_unified_diff("qualquer coisa\n+não é diff\n").is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::config::Settings;
+use crate::diff::FileDiff;
+use crate::i18n::Messages;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+/// Arquivos que raramente dizem algo ao modelo e custam muitos tokens.
+pub const DEFAULT_EXCLUDES: &[&str] = &[
+    // lockfiles
+    "**/package-lock.json",
+    "**/npm-shrinkwrap.json",
+    "**/yarn.lock",
+    "**/pnpm-lock.yaml",
+    "**/Cargo.lock",
+    "**/Gemfile.lock",
+    "**/composer.lock",
+    "**/poetry.lock",
+    "**/go.sum",
+    // codigo de terceiros
+    "**/vendor/**",
+    "**/node_modules/**",
+    "**/third_party/**",
+    // codigo gerado
+    "**/generated/**",
+    "**/generated-sources/**",
+    "**/*.generated.*",
+    "**/*.pb.go",
+    "**/*_pb2.py",
+    // assets minificados
+    "**/*.min.js",
+    "**/*.min.css",
+    "**/*.map",
+    // snapshots de testes
+    "**/__snapshots__/**",
+    "**/*.snap",
+];
+
+/// Arquivo que ficou fora do prompt, com o motivo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExcludedFile {
+    pub path: String,
+    pub insertions: u32,
+    pub deletions: u32,
+    /// Padrão que excluiu o arquivo, ou `None` quando ele não bateu com nenhum `include`.
+    pub pattern: Option<String>,
+}
+
+/// Filtro de caminhos montado a partir de `include`, `exclude` e dos padrões embutidos.
+pub struct PathFilter {
+    include: GlobSet,
+    has_include: bool,
+    exclude: GlobSet,
+    exclude_patterns: Vec<String>,
+}
+
+impl PathFilter {
+    pub fn new(settings: &Settings) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut exclude_patterns: Vec<String> = Vec::new();
+        if settings.default_excludes {
+            exclude_patterns.extend(DEFAULT_EXCLUDES.iter().map(|p| p.to_string()));
+        }
+        exclude_patterns.extend(settings.exclude.iter().cloned());
+
+        let m = settings.locale.messages();
+        Ok(PathFilter {
+            include: build_set(&settings.include, m)?,
+            has_include: !settings.include.is_empty(),
+            exclude: build_set(&exclude_patterns, m)?,
+            exclude_patterns,
+        })
+    }
+
+    // None quando o arquivo deve ir pro modelo
+    fn reason(&self, path: &str) -> Option<Option<String>> {
+        if let Some(&i) = self.exclude.matches(path).first() {
+            return Some(Some(self.exclude_patterns[i].clone()));
+        }
+        if self.has_include && !self.include.is_match(path) {
+            return Some(None);
+        }
+        None
+    }
+
+    /// Separa os arquivos que vão para o modelo dos que ficam só listados no relatório.
+    pub fn partition(&self, files: Vec<FileDiff>) -> (Vec<FileDiff>, Vec<ExcludedFile>) {
+        let mut kept = Vec::new();
+        let mut excluded = Vec::new();
+
+        for file in files {
+            // renomeacao vale pelos dois nomes: sair de vendor/ tambem e interessante
+            let reason = match &file.old_path {
+                Some(old) if old != file.path() => self.reason(file.path()).filter(|_| self.reason(old).is_some()),
+                _ => self.reason(file.path()),
+            };
+            match reason {
+                Some(pattern) => excluded.push(ExcludedFile {
+                    path: file.path().to_string(),
+                    insertions: file.insertions(),
+                    deletions: file.deletions(),
+                    pattern,
+                }),
+                None => kept.push(file),
+            }
+        }
+        (kept, excluded)
+    }
+}
+
+fn build_set(patterns: &[String], m: &Messages) -> Result<GlobSet, Box<dyn std::error::Error>> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| (m.invalid_pattern)(pattern, &e))?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parse_unified_diff;
+
+    fn settings(include: &[&str], exclude: &[&str], default_excludes: bool) -> Settings {
+        Settings {
+            include: include.iter().map(|p| p.to_string()).collect(),
+            exclude: exclude.iter().map(|p| p.to_string()).collect(),
+            default_excludes,
+            ..Settings::default()
+        }
+    }
+
+    fn modified(paths: &[&str]) -> Vec<FileDiff> {
+        let diff: String = paths
+            .iter()
+            .map(|p| format!("diff --git a/{p} b/{p}\n--- a/{p}\n+++ b/{p}\n@@ -1 +1 @@\n-a\n+b\n"))
+            .collect();
+        parse_unified_diff(&diff)
+    }
+
+    fn renamed(from: &str, to: &str) -> Vec<FileDiff> {
+        parse_unified_diff(&format!(
+            "diff --git a/{from} b/{to}\nsimilarity index 100%\nrename from {from}\nrename to {to}\n"
+        ))
+    }
+
+    // (caminhos mantidos, (caminho, padrao) excluidos)
+    fn split(filter: &PathFilter, files: Vec<FileDiff>) -> (Vec<String>, Vec<(String, Option<String>)>) {
+        let (kept, excluded) = filter.partition(files);
+        (
+            kept.iter().map(|f| f.path().to_string()).collect(),
+            excluded.into_iter().map(|e| (e.path, e.pattern)).collect(),
+        )
+    }
+
+    #[test]
+    fn default_excludes_can_be_turned_off() {
+        let files = || modified(&["src/main.rs", "Cargo.lock", "web/node_modules/x/index.js"]);
+
+        let (kept, excluded) = split(&PathFilter::new(&settings(&[], &[], true)).unwrap(), files());
+        assert_eq!(kept, ["src/main.rs"]);
+        assert_eq!(
+            excluded,
+            [
+                ("Cargo.lock".to_string(), Some("**/Cargo.lock".to_string())),
+                ("web/node_modules/x/index.js".to_string(), Some("**/node_modules/**".to_string())),
+            ]
+        );
+
+        let (kept, excluded) = split(&PathFilter::new(&settings(&[], &[], false)).unwrap(), files());
+        assert_eq!(kept.len(), 3);
+        assert!(excluded.is_empty());
+    }
+
+    #[test]
+    fn exclude_wins_over_include() {
+        let filter = PathFilter::new(&settings(&["src/**"], &["**/*.md"], false)).unwrap();
+        let (kept, excluded) = split(&filter, modified(&["src/a.rs", "src/README.md", "docs/guia.md", "build.rs"]));
+
+        assert_eq!(kept, ["src/a.rs"]);
+        assert_eq!(
+            excluded,
+            [
+                ("src/README.md".to_string(), Some("**/*.md".to_string())),
+                ("docs/guia.md".to_string(), Some("**/*.md".to_string())),
+                ("build.rs".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn renames_are_kept_when_either_name_passes() {
+        let filter = PathFilter::new(&settings(&[], &[], true)).unwrap();
+        let cases = [
+            ("vendor/x.rs", "src/x.rs", true),
+            ("src/x.rs", "vendor/x.rs", true),
+            ("vendor/x.rs", "vendor/y.rs", false),
+        ];
+        for (from, to, kept) in cases {
+            let (files, _) = filter.partition(renamed(from, to));
+            assert_eq!(!files.is_empty(), kept, "{from} -> {to}");
+        }
+    }
+
+    #[test]
+    fn invalid_pattern_is_an_error() {
+        assert!(PathFilter::new(&settings(&[], &["src/[a"], true)).is_err());
+        assert!(PathFilter::new(&settings(&["src/[a"], &[], true)).is_err());
+    }
+}
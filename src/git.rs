@@ -0,0 +1,365 @@
+use crate::diff::{DiffStats, parse_unified_diff};
+use crate::i18n::{msg, Messages};
+use crate::native;
+use chrono::Local;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    pub hash: String,
+    pub short_hash: String,
+    pub message: String,
+    /// Mensagem do commit depois da primeira linha.
+    pub body: String,
+    pub author: String,
+    pub date: String,
+    pub files_changed: Vec<String>,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
+// filtros repassados pro git log
+#[derive(Debug, Clone, Default)]
+pub struct CommitQuery {
+    pub range: Option<String>,
+    pub max_count: Option<u32>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub authors: Vec<String>,
+    pub grep: Vec<String>,
+    pub paths: Vec<String>,
+    pub first_parent: bool,
+    pub no_merges: bool,
+}
+
+impl CommitQuery {
+    fn to_git_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let Some(n) = self.max_count {
+            args.push(format!("-n{}", n));
+        }
+        if let Some(since) = &self.since {
+            args.push(format!("--since={}", since));
+        }
+        if let Some(until) = &self.until {
+            args.push(format!("--until={}", until));
+        }
+        for author in &self.authors {
+            args.push(format!("--author={}", author));
+        }
+        for pattern in &self.grep {
+            args.push(format!("--grep={}", pattern));
+        }
+        if self.first_parent {
+            args.push("--first-parent".to_string());
+        }
+        if self.no_merges {
+            args.push("--no-merges".to_string());
+        }
+        args.push("--pretty=format:%H".to_string());
+        if let Some(range) = &self.range {
+            args.push("--end-of-options".to_string());
+            args.push(range.clone());
+        }
+        args.push("--".to_string());
+        args.extend(self.paths.iter().cloned());
+
+        args
+    }
+
+    // texto curto pro log do terminal
+    pub fn describe(&self) -> String {
+        let m = msg();
+        let mut parts = Vec::new();
+
+        match (&self.range, self.max_count) {
+            (Some(range), Some(n)) => parts.push((m.query_up_to)(&n, range)),
+            (Some(range), None) => parts.push((m.query_range)(range)),
+            (None, Some(n)) => parts.push((m.query_last)(&n)),
+            (None, None) => parts.push(m.query_commits.to_string()),
+        }
+        if let Some(since) = &self.since {
+            parts.push((m.query_since)(since));
+        }
+        if let Some(until) = &self.until {
+            parts.push((m.query_until)(until));
+        }
+        if !self.authors.is_empty() {
+            parts.push((m.query_authors)(&self.authors.join(", ")));
+        }
+        if !self.grep.is_empty() {
+            parts.push((m.query_grep)(&self.grep.join(", ")));
+        }
+        if !self.paths.is_empty() {
+            parts.push((m.query_paths)(&self.paths.join(", ")));
+        }
+        if self.first_parent {
+            parts.push("(first-parent)".to_string());
+        }
+        if self.no_merges {
+            parts.push(m.query_no_merges.to_string());
+        }
+
+        parts.join(" ")
+    }
+}
+
+pub fn list_commits(repo_path: &Path, query: &CommitQuery) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let log_output = Command::new("git")
+        .arg("log")
+        .args(query.to_git_args())
+        .current_dir(repo_path)
+        .output()?;
+
+    if !log_output.status.success() {
+        return Err((msg().git_log_failed)(&String::from_utf8_lossy(&log_output.stderr).trim()).into());
+    }
+
+    Ok(String::from_utf8(log_output.stdout)?
+        .lines()
+        .map(String::from)
+        .collect())
+}
+
+// caminho dentro do .git, respeitando worktrees e core.hooksPath
+pub fn git_path(repo_path: &Path, name: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .arg("rev-parse")
+        .arg("--git-path")
+        .arg(name)
+        .current_dir(repo_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8(output.stderr)?.into());
+    }
+
+    let path = PathBuf::from(String::from_utf8(output.stdout)?.trim());
+    Ok(if path.is_absolute() { path } else { repo_path.join(path) })
+}
+
+pub fn commit_exists(repo_path: &Path, hash: &str) -> bool {
+    Command::new("git")
+        .arg("cat-file")
+        .arg("-e")
+        .arg(format!("{}^{{commit}}", hash))
+        .current_dir(repo_path)
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+// os 12 primeiros caracteres; revisoes mais curtas (`HEAD`, hash abreviado) ficam inteiras
+pub fn short_hash(hash: &str) -> &str {
+    hash.get(..12).unwrap_or(hash)
+}
+
+pub fn resolve_commit(repo_path: &Path, rev: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg(format!("{}^{{commit}}", rev))
+        .current_dir(repo_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err((msg().invalid_revision)(&rev).into());
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+pub fn get_commit_info(hash: &str, repo_path: &Path) -> Result<CommitInfo, Box<dyn std::error::Error>> {
+    // le direto do repositorio; se nao der (merge, formato nao suportado...), usa o git
+    native::get_commit_info(hash, repo_path)
+        .or_else(|_| get_commit_info_subprocess(hash, repo_path, get_commit_numstat(hash, repo_path)?))
+}
+
+// informacoes e diff do commit tirados da mesma leitura, sem abrir o repositorio duas vezes
+pub fn get_commit(hash: &str, repo_path: &Path) -> Result<(CommitInfo, String), Box<dyn std::error::Error>> {
+    native::get_commit(hash, repo_path).or_else(|_| {
+        let diff = get_commit_diff_subprocess(hash, repo_path)?;
+        // estatisticas contadas no mesmo diff que vai pro modelo
+        let stats = DiffStats::from_files(&parse_unified_diff(&diff));
+        Ok((get_commit_info_subprocess(hash, repo_path, stats)?, diff))
+    })
+}
+
+fn get_commit_info_subprocess(hash: &str, repo_path: &Path, stats: DiffStats) -> Result<CommitInfo, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .arg("show")
+        .arg("-s")
+        .arg("--pretty=format:%s%n%an%n%ad%n%b")
+        .arg("--date=format:%Y-%m-%d %H:%M")
+        .arg(hash)
+        .current_dir(repo_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8(output.stderr)?.into());
+    }
+    
+    let output_str = String::from_utf8(output.stdout)?;
+    let lines: Vec<&str> = output_str.lines().collect();
+    
+    let message = lines.first().unwrap_or(&"").to_string();
+    let author = lines.get(1).unwrap_or(&"").to_string();
+    let date = lines.get(2).unwrap_or(&"").to_string();
+    let body = lines.get(3..).unwrap_or_default().join("\n").trim().to_string();
+
+    Ok(CommitInfo {
+        hash: hash.to_string(),
+        short_hash: short_hash(hash).to_string(),
+        message,
+        body,
+        author,
+        date,
+        files_changed: stats.files,
+        insertions: stats.insertions,
+        deletions: stats.deletions,
+    })
+}
+
+// so as contagens por arquivo, sem gerar o patch inteiro
+fn get_commit_numstat(hash: &str, repo_path: &Path) -> Result<DiffStats, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .arg("show")
+        .arg("--numstat")
+        .arg("-z")
+        .arg("-C")
+        .arg("--format=")
+        .arg(hash)
+        .current_dir(repo_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8(output.stderr)?.into());
+    }
+    Ok(DiffStats::from_numstat(&String::from_utf8(output.stdout)?))
+}
+
+fn get_commit_diff_subprocess(hash: &str, repo_path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .arg("show")
+        .arg("-C")
+        .arg(hash)
+        .current_dir(repo_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8(output.stderr)?.into());
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+// alteracoes ainda nao commitadas
+#[derive(Debug, Clone, Copy)]
+pub enum PendingChanges {
+    Staged,
+    WorkingTree,
+}
+
+impl PendingChanges {
+    fn diff_args(&self) -> &'static [&'static str] {
+        match self {
+            PendingChanges::Staged => &["--cached"],
+            PendingChanges::WorkingTree => &["HEAD"],
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PendingChanges::Staged => "staged",
+            PendingChanges::WorkingTree => "working-tree",
+        }
+    }
+}
+
+// `raw_diff` e o de get_pending_diff, que quem chama ja tem pra analise
+pub fn get_pending_info(
+    repo_path: &Path,
+    pending: PendingChanges,
+    raw_diff: &str,
+    message: &str,
+    m: &Messages,
+) -> Result<CommitInfo, Box<dyn std::error::Error>> {
+    let stats = DiffStats::from_files(&parse_unified_diff(raw_diff));
+
+    let author = Command::new("git")
+        .arg("config")
+        .arg("user.name")
+        .current_dir(repo_path)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| m.unknown_author.to_string());
+
+    Ok(CommitInfo {
+        hash: pending.label().to_string(),
+        short_hash: pending.label().to_string(),
+        message: message.to_string(),
+        body: String::new(),
+        author,
+        date: Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        files_changed: stats.files,
+        insertions: stats.insertions,
+        deletions: stats.deletions,
+    })
+}
+
+pub fn get_pending_diff(repo_path: &Path, pending: PendingChanges) -> Result<String, Box<dyn std::error::Error>> {
+    run_git_diff(repo_path, pending.diff_args(), &[])
+}
+
+pub fn merge_base(repo_path: &Path, base: &str, head: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .arg("merge-base")
+        .arg(base)
+        .arg(head)
+        .current_dir(repo_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err((msg().no_merge_base)(&base, &head).into());
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+// diff combinado de `from` ate `to`, como um unico commit
+// `raw_diff` e o de get_range_diff, que quem chama ja tem pra analise
+pub fn get_range_info(repo_path: &Path, to: &str, raw_diff: &str, message: &str) -> Result<CommitInfo, Box<dyn std::error::Error>> {
+    let stats = DiffStats::from_files(&parse_unified_diff(raw_diff));
+
+    let head = get_commit_info(to, repo_path)?;
+
+    Ok(CommitInfo {
+        message: message.to_string(),
+        body: String::new(),
+        files_changed: stats.files,
+        insertions: stats.insertions,
+        deletions: stats.deletions,
+        ..head
+    })
+}
+
+pub fn get_range_diff(repo_path: &Path, from: &str, to: &str) -> Result<String, Box<dyn std::error::Error>> {
+    run_git_diff(repo_path, &[from, to], &[])
+}
+
+fn run_git_diff(repo_path: &Path, revs: &[&str], extra: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .arg("diff")
+        // copias e renomeacoes viram um arquivo so, como no `git show -C`
+        .arg("-C")
+        .args(extra)
+        .args(revs)
+        .current_dir(repo_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8(output.stderr)?.into());
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
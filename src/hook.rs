@@ -0,0 +1,164 @@
+use crate::git;
+use crate::i18n::msg;
+use std::path::{Path, PathBuf};
+
+pub const HOOK_NAME: &str = "prepare-commit-msg";
+// marca que identifica hooks instalados por esta ferramenta
+const HOOK_MARKER: &str = "# instalado por git-diff-extractor";
+
+pub fn hook_path(repo_path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    git::git_path(repo_path, &format!("hooks/{}", HOOK_NAME))
+}
+
+fn is_ours(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .map(|content| content.contains(HOOK_MARKER))
+        .unwrap_or(false)
+}
+
+pub fn install(repo_path: &Path, conventional: bool) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = hook_path(repo_path)?;
+
+    if path.exists() && !is_ours(&path) {
+        return Err((msg().hook_exists)(&path.display()).into());
+    }
+
+    let exe = std::env::current_exe()?;
+    let flags = if conventional { " --conventional" } else { "" };
+    let script = format!(
+        "#!/bin/sh\n\
+         {}\n\
+         # gera uma sugestão de mensagem a partir das alterações staged\n\
+         # falhas nunca bloqueiam o commit\n\
+         \"{}\" --repo \"$(git rev-parse --show-toplevel)\" {}{} \"$@\" || true\n",
+        HOOK_MARKER,
+        exe.display(),
+        HOOK_NAME,
+        flags
+    );
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, script)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(path)
+}
+
+pub fn uninstall(repo_path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = hook_path(repo_path)?;
+
+    if !path.exists() {
+        return Err((msg().hook_missing)(&path.display()).into());
+    }
+    if !is_ours(&path) {
+        return Err((msg().hook_not_ours)(&path.display()).into());
+    }
+
+    std::fs::remove_file(&path)?;
+    Ok(path)
+}
+
+// o git so deixa o arquivo "vazio" (apenas comentarios) num `git commit` simples
+pub fn should_fill(source: Option<&str>, current: &str) -> bool {
+    if source.is_some_and(|s| !s.is_empty()) {
+        return false;
+    }
+    current
+        .lines()
+        .all(|line| line.trim().is_empty() || line.starts_with('#'))
+}
+
+pub fn write_message(path: &Path, message: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let current = std::fs::read_to_string(path).unwrap_or_default();
+    let content = format!("{}\n\n{}", message.trim_end(), current.trim_start_matches('\n'));
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, temp_repo, TempDir};
+
+    #[test]
+    fn install_writes_an_executable_hook_and_can_replace_its_own() {
+        let repo = temp_repo();
+        let path = install(repo.path(), false).unwrap();
+
+        assert_eq!(path, repo.path().join(".git/hooks").join(HOOK_NAME));
+        let script = std::fs::read_to_string(&path).unwrap();
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains(HOOK_MARKER));
+        assert!(!script.contains("--conventional"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o755);
+        }
+
+        install(repo.path(), true).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("--conventional"));
+    }
+
+    #[test]
+    fn install_follows_core_hooks_path() {
+        let repo = temp_repo();
+        git(repo.path(), &["config", "core.hooksPath", "meus-hooks"]);
+
+        let path = install(repo.path(), false).unwrap();
+        assert_eq!(path, repo.path().join("meus-hooks").join(HOOK_NAME));
+    }
+
+    #[test]
+    fn foreign_hooks_are_never_touched() {
+        let repo = temp_repo();
+        let path = hook_path(repo.path()).unwrap();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "#!/bin/sh\necho outro hook\n").unwrap();
+
+        assert!(install(repo.path(), false).is_err());
+        assert!(uninstall(repo.path()).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "#!/bin/sh\necho outro hook\n");
+    }
+
+    #[test]
+    fn uninstall_removes_only_an_installed_hook() {
+        let repo = temp_repo();
+        assert!(uninstall(repo.path()).is_err());
+
+        let path = install(repo.path(), false).unwrap();
+        assert_eq!(uninstall(repo.path()).unwrap(), path);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn fills_only_plain_commits_with_an_empty_message() {
+        let template = "\n# Please enter the commit message for your changes.\n#\n# On branch main\n";
+        assert!(should_fill(None, template));
+        assert!(should_fill(Some(""), template));
+        assert!(should_fill(None, ""));
+
+        // -m/-F, template, merge, squash e amend/-c
+        for source in ["message", "template", "merge", "squash", "commit"] {
+            assert!(!should_fill(Some(source), template), "{}", source);
+        }
+        assert!(!should_fill(None, "mensagem já escrita\n# comentário\n"));
+    }
+
+    #[test]
+    fn write_message_goes_above_the_comments() {
+        let dir = TempDir::new();
+        let path = dir.path().join("COMMIT_EDITMSG");
+        std::fs::write(&path, "\n# Please enter the commit message\n").unwrap();
+
+        write_message(&path, "feat: adiciona soma\n\ncorpo\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "feat: adiciona soma\n\ncorpo\n\n# Please enter the commit message\n");
+    }
+}
@@ -0,0 +1,380 @@
+//! Idioma das mensagens do terminal, do relatório e dos prompts.
+//!
+//! Cada idioma tem um catálogo ([`Messages`]) com todos os textos. O [`Analyzer`] e o que ele
+//! usa (prompts, backends, relatórios) recebem o catálogo do `locale` das configurações;
+//! [`msg`] é o do processo, para o terminal do binário e os erros fora de uma análise. Os
+//! textos com valores são funções que montam a frase, já que a ordem das palavras muda de um
+//! idioma para o outro.
+//!
+//! [`Analyzer`]: crate::Analyzer
+
+mod en;
+mod pt_br;
+
+use crate::profile::Section;
+use serde::Deserialize;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Locale {
+    #[serde(rename = "pt-BR")]
+    PtBr,
+    #[serde(rename = "en")]
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::PtBr, Locale::En];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::PtBr => "pt-BR",
+            Locale::En => "en",
+        }
+    }
+
+    pub fn messages(&self) -> &'static Messages {
+        match self {
+            Locale::PtBr => &pt_br::MESSAGES,
+            Locale::En => &en::MESSAGES,
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Locale::ALL.into_iter().find(|locale| locale.name() == s).ok_or_else(|| {
+            let names: Vec<&str> = Locale::ALL.iter().map(|l| l.name()).collect();
+            (msg().unknown_locale)(&s, &names.join(", "))
+        })
+    }
+}
+
+// indice em Locale::ALL; o padrao e o primeiro
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// Troca o idioma das mensagens do processo; as análises seguem o `locale` das configurações.
+pub fn set_locale(locale: Locale) {
+    let index = Locale::ALL.iter().position(|l| *l == locale).unwrap_or(0);
+    CURRENT.store(index as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    Locale::ALL[CURRENT.load(Ordering::Relaxed) as usize]
+}
+
+/// Catálogo do idioma do processo.
+pub fn msg() -> &'static Messages {
+    locale().messages()
+}
+
+type Text = &'static str;
+type Fmt1 = fn(&dyn Display) -> String;
+type Fmt2 = fn(&dyn Display, &dyn Display) -> String;
+type Fmt3 = fn(&dyn Display, &dyn Display, &dyn Display) -> String;
+
+/// Textos de um perfil de análise.
+pub struct ProfileText {
+    pub description: Text,
+    /// Primeira frase de todos os prompts de análise.
+    pub role: Text,
+    /// O pedido da resposta final, seguido das seções.
+    pub task: Text,
+    /// Seções esperadas na resposta final, na ordem.
+    pub sections: &'static [Section],
+    /// O que observar em cada pedaço ou arquivo antes da resposta final.
+    pub focus: &'static [Text],
+    /// Contexto usado quando `project_context` não é definido.
+    pub default_context: Text,
+    pub commit_title: Text,
+    pub branch_title: Text,
+    /// Título da seção com a resposta do modelo.
+    pub analysis_heading: Text,
+}
+
+/// Todos os textos que o usuário ou o modelo leem, de um idioma.
+pub struct Messages {
+    // etiquetas do terminal que mudam com o idioma
+    pub label_model: Text,
+    pub label_processing: Text,
+    pub label_success: Text,
+    pub label_skipped: Text,
+    pub label_error: Text,
+    pub label_summary: Text,
+    pub label_done: Text,
+    pub label_risk: Text,
+    pub label_interrupted: Text,
+    pub label_filter: Text,
+    pub label_info: Text,
+    pub label_repo: Text,
+    pub label_chunk: Text,
+    pub label_proc: Text,
+    pub label_config: Text,
+    pub label_cache: Text,
+
+    // linha de comando
+    pub cli_about: Text,
+    /// Ajuda dos subcomandos e argumentos, por `"subcomando argumento"`; argumentos que
+    /// aparecem em vários subcomandos com o mesmo texto usam só o nome.
+    pub cli_help: &'static [(&'static str, Text)],
+    /// Nomes dos valores na ajuda (`--repo <CAMINHO>`), pelo `value_name` de `cli.rs` ou, sem
+    /// ele, pelo nome do argumento em maiúsculas.
+    pub cli_value_names: &'static [(&'static str, Text)],
+
+    // config e cache
+    pub config_files: Text,
+    pub config_user: Text,
+    pub config_repo: Text,
+    pub config_read: Text,
+    pub config_not_found: Text,
+    pub source_default: Text,
+    pub source_file: Fmt1,
+    pub source_env: Fmt1,
+    pub source_cli: Text,
+    pub cache_empty: Text,
+    pub cache_unreadable: Text,
+    pub cache_stale: Text,
+    pub cache_summary: Fmt2,
+    pub cache_pruned: Fmt1,
+
+    // execucao
+    pub analyzing: Fmt1,
+    pub generating_report: Fmt1,
+    pub analyzing_branch: Fmt2,
+    pub analyzing_staged: Text,
+    pub analyzing_worktree: Text,
+    pub no_commits: Text,
+    pub cancelling: Text,
+    pub internal_failure: Fmt1,
+    pub processing_commit: Fmt2,
+    pub analysis_saved: Fmt1,
+    pub commit_skipped: Fmt2,
+    pub commit_cached: Fmt2,
+    pub commit_failed: Fmt2,
+    pub no_code_changes: Text,
+    pub summary_processed: Text,
+    pub summary_skipped: Text,
+    pub summary_cached: Text,
+    pub summary_errors: Text,
+    pub summary_not_processed: Text,
+    pub summary_categories: Text,
+    pub summary_top_risks: Fmt2,
+    pub interrupted_by_user: Text,
+    pub analysis_finished: Text,
+    pub pending_message: Text,
+    pub no_pending_changes: Fmt1,
+    pub no_branch_changes: Fmt2,
+    pub no_staged_changes: Text,
+    pub risk_not_reported: Text,
+    pub risk_low: Text,
+    pub risk_medium: Text,
+    pub risk_high: Text,
+    pub message_written: Fmt1,
+    pub hook_installed: Fmt2,
+    pub hook_removed: Fmt1,
+    pub hook_exists: Fmt1,
+    pub hook_missing: Fmt1,
+    pub hook_not_ours: Fmt1,
+
+    // analise
+    pub header_commit: Text,
+    pub header_message: Text,
+    pub header_author: Text,
+    pub header_at: Text,
+    pub header_changes: Text,
+    pub header_lines_in_files: Fmt1,
+    pub merge_base: Text,
+    pub commits: Text,
+    pub branch_message: Fmt3,
+    pub diff_size: Text,
+    pub diff_size_tokens: Fmt2,
+    pub diff_too_large: Text,
+    pub diff_too_large_message: Text,
+    pub too_many_chunks: Fmt2,
+    pub split_into: Fmt1,
+    pub chunk_progress: Fmt3,
+    pub too_many_groups: Fmt2,
+    pub files_in_analyses: Fmt2,
+    pub file_progress: fn(&dyn Display, &dyn Display, &dyn Display, &dyn Display) -> String,
+    pub file_too_large: Fmt1,
+    pub reducing: Fmt2,
+    pub group_progress: Fmt3,
+    pub item_chunk: Text,
+    pub item_file: Text,
+    pub item_group: Text,
+    pub all_items_failed: Fmt1,
+    pub item_failed: fn(&dyn Display, &dyn Display, &dyn Display, &dyn Display) -> String,
+    pub item_error: Fmt3,
+    pub no_chunks: Text,
+    pub context_too_small: Fmt2,
+    pub empty_commit_message: Text,
+    pub excluded_by_filters: Fmt1,
+    pub analyzed_with: Fmt1,
+    pub not_analyzed: Text,
+    pub placeholder_analysis: Fmt1,
+    pub unknown_template: Fmt2,
+
+    // modelo
+    /// Texto do `Display` de [`crate::llm::Cancelled`], o erro de uma requisição interrompida pelo Ctrl-C.
+    pub cancelled: Text,
+    /// Anexada quando o stream cai no meio; respostas assim não vão pro cache.
+    pub partial_marker: Text,
+    pub sending_request: Fmt2,
+    pub response_received: Text,
+    pub empty_response: Text,
+    pub retrying: Fmt2,
+    pub all_attempts_failed: Text,
+    pub current_thread_runtime: Text,
+    pub echo_response: Fmt2,
+    pub context_query_failed: Fmt1,
+    pub num_ctx_capped: Fmt2,
+    pub context_window: Fmt1,
+    pub json_error: Fmt1,
+    pub idle_timeout: Fmt1,
+    pub connection_error: Fmt1,
+    pub ollama_api_error: Fmt1,
+    pub openai_api_error: Fmt1,
+    pub stream_interrupted: Fmt1,
+    pub stream_ended: Text,
+    pub keeping_partial: Fmt2,
+    pub no_choices: Text,
+    pub structured_fallback: Fmt1,
+    pub json_missing: Text,
+    pub json_empty_purpose: Text,
+
+    // git
+    pub query_up_to: Fmt2,
+    pub query_range: Fmt1,
+    pub query_last: Fmt1,
+    pub query_commits: Text,
+    pub query_since: Fmt1,
+    pub query_until: Fmt1,
+    pub query_authors: Fmt1,
+    pub query_grep: Fmt1,
+    pub query_paths: Fmt1,
+    pub query_no_merges: Text,
+    pub git_log_failed: Fmt1,
+    pub invalid_revision: Fmt1,
+    pub unknown_author: Text,
+    pub no_merge_base: Fmt2,
+
+    // validacao
+    pub unknown_backend: Fmt2,
+    pub unknown_profile: Fmt2,
+    pub unknown_locale: Fmt2,
+    pub read_failed: Fmt2,
+    pub invalid_config: Fmt2,
+    pub invalid_env: Fmt3,
+    pub empty_model: Text,
+    pub invalid_url_scheme: Fmt1,
+    pub invalid_url: Fmt2,
+    pub must_be_positive: Fmt1,
+    pub answer_tokens_too_large: Fmt2,
+    pub repo_not_found: Fmt1,
+    pub git_unavailable: Fmt1,
+    pub not_a_repo: Fmt1,
+    pub invalid_pattern: Fmt2,
+
+    // templates
+    pub templates_dir_unreadable: Fmt2,
+    pub template_error: Fmt2,
+    pub template_unknown_variable: Fmt2,
+    pub template_unclosed_tag: Text,
+    pub template_stray_else: Text,
+    pub template_stray_end: Text,
+    pub template_unclosed_if: Fmt1,
+
+    // prompts
+    /// Idioma pedido ao modelo, como `{{language}}` nos templates.
+    pub language: Text,
+    pub prompt_stats: Fmt3,
+    pub prompt_chunk_item: Fmt1,
+    pub prompt_part_item: Fmt1,
+    pub yes: Text,
+    pub no: Text,
+    /// Pedida em toda análise fora do modo estruturado; o título é o que `extract_risk_level` procura.
+    pub risk_section: Section,
+    /// Substitui as seções no modo estruturado, seguida dos campos do JSON.
+    pub json_instructions: Text,
+    /// Descrição de cada campo, na ordem de [`crate::analysis::FIELDS`].
+    pub json_fields: [Text; 7],
+    /// Completa a descrição de `observations` com as seções do perfil.
+    pub json_covering: Fmt1,
+    pub templates: [Text; 7],
+    /// Na ordem de [`crate::profile::Profile::ALL`].
+    pub profiles: [ProfileText; 5],
+
+    // diff
+    pub file_added: Text,
+    pub file_deleted: Text,
+    pub file_renamed: Fmt1,
+    pub file_copied: Fmt1,
+    pub similarity: Fmt1,
+    pub mode_change: Fmt2,
+    pub mode_now: Fmt1,
+    pub mode_executable: Text,
+    pub mode_symlink: Text,
+    pub mode_submodule: Text,
+    pub file_binary: Text,
+    pub file_modified: Text,
+
+    // relatorio
+    pub file_heading: Text,
+    pub commit_info: Text,
+    pub full_hash: Text,
+    pub short_hash: Text,
+    pub author: Text,
+    pub commit_date: Text,
+    pub files_modified: Text,
+    pub lines_added: Text,
+    pub lines_removed: Text,
+    pub untitled: Text,
+    pub untitled_file: Text,
+    pub branch_info: Text,
+    pub details: Text,
+    pub files: Text,
+    pub excluded_files: Text,
+    pub outside_include: Text,
+    pub per_file_analyses: Text,
+    pub chunks_analyzed: Text,
+    pub reduction_depth: Fmt1,
+    pub appendix: Text,
+    pub appendix_changes: Fmt3,
+    pub generated_at: Text,
+    pub col_file: Text,
+    pub col_kind: Text,
+    pub col_changes: Text,
+    pub col_reason: Text,
+    pub col_hash: Text,
+    pub col_date: Text,
+    pub col_author: Text,
+    pub col_message: Text,
+    pub col_score: Text,
+    pub col_risk: Text,
+    pub col_categories: Text,
+    pub risk_score: Text,
+    pub field_purpose: Text,
+    pub field_impact: Text,
+    pub field_observations: Text,
+    pub field_risk: Text,
+    pub field_categories: Text,
+    pub field_components: Text,
+    pub field_breaking_change: Text,
+}
+
+// so a identificacao; os textos nao ajudam a depurar
+impl fmt::Debug for Messages {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Messages").field("language", &self.language).finish_non_exhaustive()
+    }
+}
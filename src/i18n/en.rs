@@ -0,0 +1,454 @@
+use super::{Messages, ProfileText};
+
+pub static MESSAGES: Messages = Messages {
+    label_model: "MODEL",
+    label_processing: "PROCESSING",
+    label_success: "SUCCESS",
+    label_skipped: "SKIPPED",
+    label_error: "ERROR",
+    label_summary: "SUMMARY",
+    label_done: "DONE",
+    label_risk: "RISK",
+    label_interrupted: "INTERRUPTED",
+    label_filter: "FILTER",
+    label_info: "INFO",
+    label_repo: "REPO",
+    label_chunk: "CHUNK",
+    label_proc: "PROC",
+    label_config: "CONFIG",
+    label_cache: "CACHE",
+
+    cli_about: "Extracts diffs from Git commits and generates technical analyses with Ollama",
+    cli_help: &[
+        ("analyze", "Analyzes the last N commits of HEAD"),
+        ("analyze count", "Number of commits (default: 10, or all of them with a date filter)"),
+        ("range", "Analyzes the commits of a revision range (e.g. v1.2..v1.3, main..feature)"),
+        ("range range", "Revision range in git's format"),
+        ("range count", "Limits the number of commits"),
+        ("force", "Analyzes again even the commits already in the cache"),
+        ("report", "Generates the report of a single commit"),
+        ("report rev", "Hash, tag or any git revision"),
+        ("staged", "Analyzes the staged (or working tree) changes before committing"),
+        ("staged worktree", "Analyzes all uncommitted changes, not only the staged ones"),
+        ("staged message", "Intended commit message (gives the model context)"),
+        ("staged output", "Saves the full report to this file"),
+        ("pr", "Analyzes a whole branch (diff since the merge-base) as a single change"),
+        ("pr base", "Target branch (e.g. main)"),
+        ("pr head", "Branch with the changes"),
+        ("pr output", "Report file (default: pr_<date>_<head>_<base>.md)"),
+        ("message", "Suggests a commit message from the staged changes"),
+        ("message conventional", "Uses the Conventional Commits format (feat:, fix:, ...)"),
+        ("prepare-commit-msg", "Hook mode: writes the suggested message to the file given by git"),
+        ("prepare-commit-msg file", "Commit message file"),
+        ("prepare-commit-msg source", "Message source (message, template, merge, squash, commit)"),
+        ("prepare-commit-msg sha", "Commit hash when amending"),
+        ("prepare-commit-msg conventional", "Uses the Conventional Commits format"),
+        ("install-hook", "Installs (or removes) the prepare-commit-msg hook in the repository"),
+        ("install-hook conventional", "Sets the hook up for Conventional Commits"),
+        ("install-hook uninstall", "Removes the hook installed by this tool"),
+        ("render", "Shows the prompt that would be sent to the model, without calling it"),
+        ("render rev", "Commit used to fill in the template"),
+        ("render template", "Template to build"),
+        ("render staged", "Uses the staged changes instead of a commit"),
+        ("config", "Inspects the configuration"),
+        ("config show", "Shows the final configuration and where each value came from"),
+        ("cache", "Inspects or clears the analysis cache (.git/diff-extractor/cache)"),
+        ("cache show", "Lists the stored analyses"),
+        ("cache prune", "Removes entries from another model, prompt or configuration, and from commits that no longer exist"),
+        ("cache prune all", "Removes every entry"),
+        ("since", "Only commits since this date (e.g. \"2 weeks ago\", 2024-01-31)"),
+        ("until", "Only commits up to this date"),
+        ("author", "Filters by author (can be repeated)"),
+        ("grep", "Filters by commit message (can be repeated)"),
+        ("first_parent", "Follows only the first parent of merges"),
+        ("no_merges", "Skips merge commits"),
+        ("paths", "Only commits that touch these paths (after `--`)"),
+        ("repo", "Path of the git repository"),
+        ("backend", "Inference server: ollama, ollama-chat, openai or echo"),
+        ("model", "Model used for the analyses"),
+        ("ollama_url", "Base URL of the model server"),
+        ("api_key", "API key (openai backend)"),
+        ("max_diff_size", "Maximum diff size before splitting it into chunks (tokens; default: what fits in the context)"),
+        ("chunk_size", "Maximum size of each diff chunk (tokens; default: what fits in the context)"),
+        ("num_ctx", "Model context window (default: asks Ollama)"),
+        ("answer_tokens", "Tokens reserved for the model's answer"),
+        ("max_retries", "Attempts per request to the model"),
+        ("retry_delay_ms", "Delay between attempts (ms)"),
+        ("timeout_secs", "Timeout of the requests to the model (seconds)"),
+        ("stream", "Shows Ollama's answer token by token (true/false)"),
+        ("idle_timeout_secs", "Maximum time without receiving tokens while streaming (seconds)"),
+        ("max_chunks", "Maximum number of chunks per commit"),
+        ("per_file", "Analyzes each file (or group of small files in the same folder) separately and synthesizes at the end (`--per-file=false` turns it off)"),
+        ("structured", "Asks for the final analysis as JSON and shows its fields in the report; falls back to free text if the answer does not follow the schema"),
+        ("include", "Only sends to the model files matching this glob (can be repeated)"),
+        ("exclude", "Does not send to the model files matching this glob (can be repeated)"),
+        ("no_default_excludes", "Turns off the built-in patterns (lockfiles, vendor, generated, minified, snapshots)"),
+        ("profile", "Analysis profile: migration, review, security, changelog or onboarding"),
+        ("templates", "Directory with prompt templates (<name>.txt) that replace the built-in ones"),
+        ("project_context", "Project description used in the prompts (default: the profile's; empty removes it)"),
+        ("locale", "Language of the terminal, the report and the model's answers: pt-BR or en"),
+        ("jobs", "Commits processed at the same time"),
+        ("max_requests_per_host", "Concurrent requests to the same model server"),
+    ],
+    cli_value_names: &[
+        ("CAMINHO", "PATH"),
+        ("NOME", "NAME"),
+        ("URL", "URL"),
+        ("CHAVE", "KEY"),
+        ("TOKENS", "TOKENS"),
+        ("N", "N"),
+        ("MS", "MS"),
+        ("SEGUNDOS", "SECONDS"),
+        ("BOOL", "BOOL"),
+        ("GLOB", "GLOB"),
+        ("DIRETÓRIO", "DIRECTORY"),
+        ("TEXTO", "TEXT"),
+        ("IDIOMA", "LOCALE"),
+        ("ARQUIVO", "FILE"),
+        ("DATA", "DATE"),
+        ("PADRÃO", "PATTERN"),
+        ("RANGE", "RANGE"),
+        ("REV", "REV"),
+        ("BASE", "BASE"),
+        ("HEAD", "HEAD"),
+        ("FILE", "FILE"),
+        ("SOURCE", "SOURCE"),
+        ("SHA", "SHA"),
+        ("TEMPLATE", "TEMPLATE"),
+    ],
+
+    config_files: "Files:",
+    config_user: "user:",
+    config_repo: "repository:",
+    config_read: "read",
+    config_not_found: "not found",
+    source_default: "default",
+    source_file: |path| format!("file {}", path),
+    source_env: |var| format!("variable {}", var),
+    source_cli: "command line",
+    cache_empty: "no stored analyses",
+    cache_unreadable: "(unreadable)",
+    cache_stale: "(stale)",
+    cache_summary: |total, stale| format!("{} entry(ies), {} stale or unreadable", total, stale),
+    cache_pruned: |removed| format!("{} entry(ies) removed", removed),
+
+    analyzing: |what| format!("Analyzing {}...", what),
+    generating_report: |rev| format!("Generating report for commit {}...", rev),
+    analyzing_branch: |head, base| format!("Analyzing {} against {}...", head, base),
+    analyzing_staged: "Analyzing staged changes...",
+    analyzing_worktree: "Analyzing uncommitted changes...",
+    no_commits: "No commits found",
+    cancelling: "Cancelling in-flight requests... (press Ctrl-C again to exit immediately)",
+    internal_failure: |e| format!("internal failure: {}", e),
+    processing_commit: |index, total| format!("Commit {}/{}", index, total),
+    analysis_saved: |file| format!("Analysis saved to '{}'", file),
+    commit_skipped: |hash, reason| format!("Commit {}: {}", hash, reason),
+    commit_cached: |hash, file| format!("Commit {} already analyzed in '{}' (use --force to redo it)", hash, file),
+    commit_failed: |hash, e| format!("Commit {}: {}", hash, e),
+    no_code_changes: "no code changes detected",
+    summary_processed: "Processed:",
+    summary_skipped: "Skipped:",
+    summary_cached: "Cached:",
+    summary_errors: "Errors:",
+    summary_not_processed: "Not processed:",
+    summary_categories: "Categories:",
+    summary_top_risks: |shown, total| format!("Riskiest commits ({} of {}):", shown, total),
+    interrupted_by_user: "analysis interrupted by the user",
+    analysis_finished: "Analysis finished!",
+    pending_message: "Changes not yet committed",
+    no_pending_changes: |kind| format!("No {} changes found", kind),
+    no_branch_changes: |head, base| format!("No changes in '{}' that are not in '{}'", head, base),
+    no_staged_changes: "No staged changes found",
+    risk_not_reported: "The model did not report a risk level",
+    risk_low: "LOW",
+    risk_medium: "MEDIUM",
+    risk_high: "HIGH",
+    message_written: |file| format!("Suggested message written to '{}'", file),
+    hook_installed: |name, path| format!("Hook {} installed at '{}'", name, path),
+    hook_removed: |path| format!("Hook removed from '{}'", path),
+    hook_exists: |path| {
+        format!("there is already a hook at '{}' that was not installed by this tool; remove it or merge it by hand", path)
+    },
+    hook_missing: |path| format!("no hook installed at '{}'", path),
+    hook_not_ours: |path| format!("the hook at '{}' was not installed by this tool; nothing removed", path),
+
+    header_commit: "Commit:",
+    header_message: "Message:",
+    header_author: "Author:",
+    header_at: "on",
+    header_changes: "Changes:",
+    header_lines_in_files: |files| format!("lines in {} file(s)", files),
+    merge_base: "Merge-base:",
+    commits: "Commits:",
+    branch_message: |head, base, count| format!("Branch {} on top of {} ({} commits):", head, base, count),
+    diff_size: "Diff size:",
+    diff_size_tokens: |tokens, limit| format!("~{} tokens ({} fit in a request)", tokens, limit),
+    diff_too_large: "Diff too large, splitting into chunks...",
+    diff_too_large_message: "Diff too large, summarizing before writing the message...",
+    too_many_chunks: |count, max| {
+        format!("Warning: {} chunks exceed the maximum of {}. Processing only the first {} chunks.", count, max, max)
+    },
+    split_into: |count| format!("Split into {} chunks", count),
+    chunk_progress: |index, total, tokens| format!("Chunk {}/{} (~{} tokens)", index, total, tokens),
+    too_many_groups: |count, max| {
+        format!("Warning: {} file groups exceed the maximum of {}. Analyzing only the first {}.", count, max, max)
+    },
+    files_in_analyses: |files, groups| format!("{} file(s) in {} analysis(es)", files, groups),
+    file_progress: |index, total, files, tokens| format!("File {}/{}: {} (~{} tokens)", index, total, files, tokens),
+    file_too_large: |path| format!("{} does not fit in a request, splitting into chunks...", path),
+    reducing: |groups, depth| format!("The analyses do not fit in the context, summarizing in {} groups (level {})", groups, depth),
+    group_progress: |index, total, depth| format!("Group {}/{} of level {}", index, total, depth),
+    item_chunk: "chunk",
+    item_file: "file",
+    item_group: "group",
+    all_items_failed: |item| format!("All {}s failed", item),
+    item_failed: |item, index, total, e| format!("Error in {} {}/{}: {} - continuing...", item, index, total, e),
+    item_error: |item, index, e| format!("**Error in {} {}:** {}", item, index, e),
+    no_chunks: "No diff chunks found",
+    context_too_small: |context, answer| {
+        format!(
+            "the {}-token context window cannot hold the prompt and the {} tokens reserved for the answer; raise num_ctx or lower answer_tokens",
+            context, answer
+        )
+    },
+    empty_commit_message: "the model did not return a commit message",
+    excluded_by_filters: |count| format!("{} file(s) left out of the prompt by the path filters", count),
+    analyzed_with: |path| format!("*Analyzed together with `{}`.*", path),
+    not_analyzed: "*Not analyzed: chunk limit reached.*",
+    placeholder_analysis: |index| format!("(analysis {} written by the model)", index),
+    unknown_template: |name, names| format!("unknown template '{}' (use {})", name, names),
+
+    cancelled: "cancelled by the user",
+    partial_marker: "*[Partial answer",
+    sending_request: |attempt, total| format!("Sending request... (attempt {}/{})", attempt, total),
+    response_received: "Response received",
+    empty_response: "Empty response from the model",
+    retrying: |e, delay| format!("{} - retrying in {}ms...", e, delay),
+    all_attempts_failed: "All attempts failed",
+    current_thread_runtime: "the Analyzer blocks waiting for the model and cannot run inside a current_thread tokio runtime; call it from another thread",
+    echo_response: |chars, lines| format!("[echo] prompt with {} characters and {} lines", chars, lines),
+    context_query_failed: |e| format!("Could not query the model context: {}", e),
+    num_ctx_capped: |num_ctx, max| format!("num_ctx {} exceeds the model maximum, using {}", num_ctx, max),
+    context_window: |tokens| format!("Context window: {} tokens", tokens),
+    json_error: |e| format!("Error decoding JSON response: {}", e),
+    idle_timeout: |secs| format!("No tokens received in {}s", secs),
+    connection_error: |e| format!("Connection error: {}", e),
+    ollama_api_error: |e| format!("Ollama API error: {}", e),
+    openai_api_error: |e| format!("OpenAI-compatible API error: {}", e),
+    stream_interrupted: |e| format!("Stream interrupted: {}", e),
+    stream_ended: "Stream ended before the end of the response",
+    keeping_partial: |reason, chars| format!("{} - keeping the partial response ({} characters)", reason, chars),
+    structured_fallback: |e| format!("Response does not match the JSON schema ({}), using the free text", e),
+    json_missing: "the response has no JSON object",
+    json_empty_purpose: "the `purpose` field is empty",
+    no_choices: "Response without choices",
+
+    query_up_to: |n, range| format!("up to {} commits in {}", n, range),
+    query_range: |range| format!("commits in {}", range),
+    query_last: |n| format!("last {} commits", n),
+    query_commits: "commits",
+    query_since: |date| format!("since {}", date),
+    query_until: |date| format!("until {}", date),
+    query_authors: |authors| format!("by {}", authors),
+    query_grep: |patterns| format!("with message containing {}", patterns),
+    query_paths: |paths| format!("in {}", paths),
+    query_no_merges: "(no merges)",
+    git_log_failed: |e| format!("git log failed: {}", e),
+    invalid_revision: |rev| format!("invalid revision '{}'", rev),
+    unknown_author: "unknown",
+    no_merge_base: |base, head| format!("there is no merge-base between '{}' and '{}'", base, head),
+
+    unknown_backend: |name, names| format!("unknown backend '{}' (use {})", name, names),
+    unknown_profile: |name, names| format!("unknown profile '{}' (use {})", name, names),
+    unknown_locale: |name, names| format!("unknown locale '{}' (use {})", name, names),
+    read_failed: |path, e| format!("could not read '{}': {}", path, e),
+    invalid_config: |path, e| format!("invalid configuration in '{}': {}", path, e),
+    invalid_env: |var, value, e| format!("invalid variable {} '{}': {}", var, value, e),
+    empty_model: "the model name cannot be empty",
+    invalid_url_scheme: |scheme| format!("server URL with invalid scheme '{}': use http or https", scheme),
+    invalid_url: |url, e| format!("invalid server URL '{}': {}", url, e),
+    must_be_positive: |name| format!("{} must be greater than zero", name),
+    answer_tokens_too_large: |answer, num_ctx| format!("answer_tokens ({}) must be less than num_ctx ({})", answer, num_ctx),
+    repo_not_found: |path| format!("repository not found: '{}'", path),
+    git_unavailable: |e| format!("could not run git: {}", e),
+    not_a_repo: |path| format!("'{}' is not a git repository", path),
+    invalid_pattern: |pattern, e| format!("invalid path pattern '{}': {}", pattern, e),
+
+    templates_dir_unreadable: |dir, e| format!("could not read the templates directory '{}': {}", dir, e),
+    template_error: |name, e| format!("template '{}': {}", name, e),
+    template_unknown_variable: |name, names| format!("unknown variable `{}` (use {})", name, names),
+    template_unclosed_tag: "`{{` without a matching `}}`",
+    template_stray_else: "`{{else}}` outside an `{{#if}}`",
+    template_stray_end: "`{{/if}}` without `{{#if}}`",
+    template_unclosed_if: |var| format!("`{{{{#if {}}}}}` without `{{{{/if}}}}`", var),
+
+    language: "English",
+    prompt_stats: |files, insertions, deletions| format!("{} file(s), +{} -{} lines", files, insertions, deletions),
+    prompt_chunk_item: |index| format!("**Chunk {}:**", index),
+    prompt_part_item: |index| format!("**Part {}:**", index),
+    yes: "yes",
+    no: "no",
+    risk_section: ("RISK", "Rate the risk of these changes as LOW, MEDIUM or HIGH and justify it in one sentence"),
+    json_instructions: "Answer ONLY with a JSON object, with no text before or after it, with the fields:",
+    json_fields: [
+        "What this change is meant to solve/implement",
+        "How it affects the system",
+        "Important points, risks or considerations",
+        "Risk of shipping the change: `low`, `medium` or `high`",
+        "Kinds of change, among: `refactor`, `bugfix`, `feature`, `dependency`, `config`, `schema`, `test-only`",
+        "Affected modules, packages or layers",
+        "`true` if the change breaks compatibility for users of the code",
+    ],
+    json_covering: |sections| format!("covering {}", sections),
+    templates: [
+        include_str!("../../templates/en/analysis.txt"),
+        include_str!("../../templates/en/chunk.txt"),
+        include_str!("../../templates/en/summary.txt"),
+        include_str!("../../templates/en/file_analysis.txt"),
+        include_str!("../../templates/en/file_synthesis.txt"),
+        include_str!("../../templates/en/group_summary.txt"),
+        include_str!("../../templates/en/commit_message.txt"),
+    ],
+    profiles: [
+        ProfileText {
+            description: "purpose and impact of the commits of a migration",
+            role: "You are a senior software engineer specialized in migrations.",
+            task: "Analyze this commit and provide a structured summary explaining:",
+            sections: &[
+                ("PURPOSE", "What this change intends to solve/implement"),
+                ("IMPACT", "How it affects the system and the migration"),
+                ("NOTES", "Important points, risks or considerations"),
+            ],
+            focus: &["Main changes", "Specific purpose of the changes", "Relevant technical impact"],
+            default_context: "Migration from Java 8 → 17 and Hibernate 5 → 6",
+            commit_title: "Commit Analysis",
+            branch_title: "Branch Analysis",
+            analysis_heading: "Technical Analysis",
+        },
+        ProfileText {
+            description: "code review style critique",
+            role: "You are a senior code reviewer, thorough and objective.",
+            task: "Review this commit as in a code review and point out:",
+            sections: &[
+                ("PROBLEMS", "Bugs, logic errors, unhandled cases and likely regressions, citing file and line"),
+                ("QUALITY", "Readability, naming, duplication and adherence to the style of the surrounding code"),
+                ("TESTS", "What should be tested and is not"),
+                ("SUGGESTIONS", "Concrete changes, from most to least important"),
+            ],
+            focus: &["Bugs and logic errors", "Readability or maintenance problems", "Missing tests"],
+            default_context: "",
+            commit_title: "Commit Review",
+            branch_title: "Branch Review",
+            analysis_heading: "Code Review",
+        },
+        ProfileText {
+            description: "vulnerabilities, attack surface and secrets",
+            role: "You are an application security engineer reviewing code changes.",
+            task: "Analyze this commit from a security standpoint and point out:",
+            sections: &[
+                (
+                    "VULNERABILITIES",
+                    "Flaws introduced or fixed (injection, authentication, authorization, cryptography, data exposure), with file and line",
+                ),
+                ("ATTACK SURFACE", "New or changed inputs, endpoints, permissions or dependencies"),
+                ("SECRETS", "Credentials, keys or sensitive data present in the diff"),
+                ("RECOMMENDATIONS", "Suggested fixes and checks, from most to least severe"),
+            ],
+            focus: &[
+                "Unvalidated inputs and injection points",
+                "Changes to authentication, authorization or cryptography",
+                "Exposed secrets or sensitive data",
+            ],
+            default_context: "",
+            commit_title: "Commit Security Analysis",
+            branch_title: "Branch Security Analysis",
+            analysis_heading: "Security Analysis",
+        },
+        ProfileText {
+            description: "changelog entry for the project's users",
+            role: "You are a maintainer writing the project changelog for its users.",
+            task: "Write the changelog entry for this commit, in the Keep a Changelog style:",
+            sections: &[
+                ("CATEGORY", "One of Added, Changed, Deprecated, Removed, Fixed or Security"),
+                ("ENTRY", "One or two lines from the point of view of the project's users, without implementation details"),
+                ("BREAKING CHANGES", "What changes for those who upgrade, or \"none\""),
+            ],
+            focus: &["Changes visible to the project's users", "Behavior fixes", "Breaking changes"],
+            default_context: "",
+            commit_title: "Commit Changelog",
+            branch_title: "Branch Changelog",
+            analysis_heading: "Changelog Entry",
+        },
+        ProfileText {
+            description: "plain-language explanation for newcomers to the team",
+            role: "You are an experienced engineer explaining the project's code to someone who just joined the team.",
+            task: "Explain this commit in plain language, without assuming knowledge of the code:",
+            sections: &[
+                ("WHAT CHANGED", "The change in a few sentences, without unnecessary jargon"),
+                ("WHY", "The problem or need behind it"),
+                ("WHERE", "The parts of the system involved and the role of each one"),
+                ("CONCEPTS", "Terms, patterns or technologies that help understand the change"),
+            ],
+            focus: &["What the changed code does", "Why it probably changed", "Concepts needed to understand the excerpt"],
+            default_context: "",
+            commit_title: "Commit Explanation",
+            branch_title: "Branch Explanation",
+            analysis_heading: "Explanation",
+        },
+    ],
+
+    file_added: "new file",
+    file_deleted: "deleted file",
+    file_renamed: |old| format!("renamed from `{}`", old),
+    file_copied: |old| format!("copied from `{}`", old),
+    similarity: |percent| format!("similarity {}%", percent),
+    mode_change: |old, new| format!("mode {} → {}", old, new),
+    mode_now: |kind| format!("now {}", kind),
+    mode_executable: "executable",
+    mode_symlink: "symbolic link",
+    mode_submodule: "submodule",
+    file_binary: "binary, content not shown",
+    file_modified: "modified",
+
+    file_heading: "File",
+    commit_info: "Commit Information",
+    full_hash: "Full Hash",
+    short_hash: "Short Hash",
+    author: "Author",
+    commit_date: "Commit Date",
+    files_modified: "Files Changed",
+    lines_added: "Lines Added",
+    lines_removed: "Lines Removed",
+    untitled: "Untitled",
+    untitled_file: "untitled",
+    branch_info: "Branch Information",
+    details: "Change Details",
+    files: "Files",
+    excluded_files: "Files left out of the analysis",
+    outside_include: "outside `include`",
+    per_file_analyses: "Per-File Analyses",
+    chunks_analyzed: "Chunks Analyzed",
+    reduction_depth: |depth| format!("reduction depth: {}", depth),
+    appendix: "Appendix: Commits",
+    appendix_changes: |insertions, deletions, files| format!("+{} -{} in {} file(s)", insertions, deletions, files),
+    generated_at: "Report generated on",
+    col_file: "File",
+    col_kind: "Kind",
+    col_changes: "Changes",
+    col_reason: "Reason",
+    col_hash: "Hash",
+    col_date: "Date",
+    col_author: "Author",
+    col_message: "Message",
+    col_score: "Score",
+    col_risk: "Risk",
+    col_categories: "Categories",
+    risk_score: "Risk Score",
+    field_purpose: "Purpose",
+    field_impact: "Impact",
+    field_observations: "Observations",
+    field_risk: "Risk",
+    field_categories: "Categories",
+    field_components: "Components",
+    field_breaking_change: "Breaking change",
+};
@@ -0,0 +1,457 @@
+use super::{Messages, ProfileText};
+
+pub static MESSAGES: Messages = Messages {
+    label_model: "MODELO",
+    label_processing: "PROCESSANDO",
+    label_success: "SUCESSO",
+    label_skipped: "IGNORADO",
+    label_error: "ERRO",
+    label_summary: "RESUMO",
+    label_done: "CONCLUÍDO",
+    label_risk: "RISCO",
+    label_interrupted: "INTERROMPIDO",
+    label_filter: "FILTRO",
+    label_info: "INFO",
+    label_repo: "REPOSITÓRIO",
+    label_chunk: "PEDAÇOS",
+    label_proc: "ANÁLISE",
+    label_config: "CONFIGURAÇÃO",
+    label_cache: "CACHE",
+
+    cli_about: "Extrai diffs de commits do Git e gera análises técnicas com o Ollama",
+    cli_help: &[
+        ("analyze", "Analisa os últimos N commits do HEAD"),
+        ("analyze count", "Quantidade de commits (padrão: 10, ou todos quando há filtro de data)"),
+        ("range", "Analisa os commits de um intervalo de revisões (ex: v1.2..v1.3, main..feature)"),
+        ("range range", "Intervalo de revisões no formato do git"),
+        ("range count", "Limita a quantidade de commits"),
+        ("force", "Analisa de novo mesmo os commits que já estão no cache"),
+        ("report", "Gera o relatório de um único commit"),
+        ("report rev", "Hash, tag ou qualquer revisão do git"),
+        ("staged", "Analisa as alterações staged (ou do working tree) antes do commit"),
+        ("staged worktree", "Analisa todas as alterações não commitadas, não só as staged"),
+        ("staged message", "Mensagem pretendida para o commit (dá contexto ao modelo)"),
+        ("staged output", "Salva o relatório completo neste arquivo"),
+        ("pr", "Analisa uma branch inteira (diff desde o merge-base) como uma única alteração"),
+        ("pr base", "Branch de destino (ex: main)"),
+        ("pr head", "Branch com as alterações"),
+        ("pr output", "Arquivo do relatório (padrão: pr_<data>_<head>_<base>.md)"),
+        ("message", "Sugere uma mensagem de commit a partir das alterações staged"),
+        ("message conventional", "Usa o formato Conventional Commits (feat:, fix:, ...)"),
+        ("prepare-commit-msg", "Modo hook: escreve a mensagem sugerida no arquivo recebido do git"),
+        ("prepare-commit-msg file", "Arquivo da mensagem de commit"),
+        ("prepare-commit-msg source", "Origem da mensagem (message, template, merge, squash, commit)"),
+        ("prepare-commit-msg sha", "Hash do commit quando é um amend"),
+        ("prepare-commit-msg conventional", "Usa o formato Conventional Commits"),
+        ("install-hook", "Instala (ou remove) o hook prepare-commit-msg no repositório"),
+        ("install-hook conventional", "Configura o hook para Conventional Commits"),
+        ("install-hook uninstall", "Remove o hook instalado por esta ferramenta"),
+        ("render", "Mostra o prompt que seria enviado ao modelo, sem chamá-lo"),
+        ("render rev", "Commit usado para preencher o template"),
+        ("render template", "Template a montar"),
+        ("render staged", "Usa as alterações staged no lugar de um commit"),
+        ("config", "Inspeciona a configuração"),
+        ("config show", "Mostra a configuração final e de onde veio cada valor"),
+        ("cache", "Inspeciona ou limpa o cache de análises (.git/diff-extractor/cache)"),
+        ("cache show", "Lista as análises guardadas"),
+        ("cache prune", "Remove entradas de outro modelo, prompt ou configuração, e de commits que não existem mais"),
+        ("cache prune all", "Remove todas as entradas"),
+        ("since", "Só commits a partir desta data (ex: \"2 weeks ago\", 2024-01-31)"),
+        ("until", "Só commits até esta data"),
+        ("author", "Filtra pelo autor (pode repetir)"),
+        ("grep", "Filtra pela mensagem do commit (pode repetir)"),
+        ("first_parent", "Segue apenas o primeiro pai dos merges"),
+        ("no_merges", "Ignora commits de merge"),
+        ("paths", "Só commits que alteram estes caminhos (depois de `--`)"),
+        ("repo", "Caminho do repositório git"),
+        ("backend", "Servidor de inferência: ollama, ollama-chat, openai ou echo"),
+        ("model", "Modelo usado nas análises"),
+        ("ollama_url", "URL base do servidor do modelo"),
+        ("api_key", "Chave da API (backend openai)"),
+        ("max_diff_size", "Tamanho máximo do diff antes de dividir em pedaços (tokens; padrão: o que cabe no contexto)"),
+        ("chunk_size", "Tamanho máximo de cada pedaço do diff (tokens; padrão: o que cabe no contexto)"),
+        ("num_ctx", "Janela de contexto do modelo (padrão: consulta o Ollama)"),
+        ("answer_tokens", "Tokens reservados para a resposta do modelo"),
+        ("max_retries", "Tentativas por requisição ao modelo"),
+        ("retry_delay_ms", "Delay entre tentativas (ms)"),
+        ("timeout_secs", "Timeout das requisições ao modelo (segundos)"),
+        ("stream", "Mostra a resposta do Ollama token a token (true/false)"),
+        ("idle_timeout_secs", "Tempo máximo sem receber tokens no streaming (segundos)"),
+        ("max_chunks", "Máximo de pedaços por commit"),
+        ("per_file", "Analisa cada arquivo (ou grupo de arquivos pequenos da mesma pasta) separadamente e sintetiza no fim (`--per-file=false` desliga)"),
+        ("structured", "Pede a análise final como JSON e mostra os campos no relatório; cai no texto livre se a resposta não seguir o esquema"),
+        ("include", "Só envia ao modelo arquivos que batem com este glob (pode repetir)"),
+        ("exclude", "Não envia ao modelo arquivos que batem com este glob (pode repetir)"),
+        ("no_default_excludes", "Desliga os padrões embutidos (lockfiles, vendor, gerados, minificados, snapshots)"),
+        ("profile", "Perfil da análise: migration, review, security, changelog ou onboarding"),
+        ("templates", "Diretório com templates de prompt (<nome>.txt) que substituem os embutidos"),
+        ("project_context", "Descrição do projeto usada nos prompts (padrão: a do perfil; vazio remove)"),
+        ("locale", "Idioma do terminal, do relatório e das respostas do modelo: pt-BR ou en"),
+        ("jobs", "Commits processados ao mesmo tempo"),
+        ("max_requests_per_host", "Requisições simultâneas ao mesmo servidor do modelo"),
+    ],
+    cli_value_names: &[
+        ("CAMINHO", "CAMINHO"),
+        ("NOME", "NOME"),
+        ("URL", "URL"),
+        ("CHAVE", "CHAVE"),
+        ("TOKENS", "TOKENS"),
+        ("N", "N"),
+        ("MS", "MS"),
+        ("SEGUNDOS", "SEGUNDOS"),
+        ("BOOL", "BOOL"),
+        ("GLOB", "GLOB"),
+        ("DIRETÓRIO", "DIRETÓRIO"),
+        ("TEXTO", "TEXTO"),
+        ("IDIOMA", "IDIOMA"),
+        ("ARQUIVO", "ARQUIVO"),
+        ("DATA", "DATA"),
+        ("PADRÃO", "PADRÃO"),
+        ("RANGE", "INTERVALO"),
+        ("REV", "REVISÃO"),
+        ("BASE", "BASE"),
+        ("HEAD", "HEAD"),
+        ("FILE", "ARQUIVO"),
+        ("SOURCE", "ORIGEM"),
+        ("SHA", "SHA"),
+        ("TEMPLATE", "TEMPLATE"),
+    ],
+
+    config_files: "Arquivos:",
+    config_user: "usuário:",
+    config_repo: "repositório:",
+    config_read: "lido",
+    config_not_found: "não encontrado",
+    source_default: "padrão",
+    source_file: |path| format!("arquivo {}", path),
+    source_env: |var| format!("variável {}", var),
+    source_cli: "linha de comando",
+    cache_empty: "nenhuma análise guardada",
+    cache_unreadable: "(ilegível)",
+    cache_stale: "(desatualizada)",
+    cache_summary: |total, stale| format!("{} entrada(s), {} desatualizada(s) ou ilegível(is)", total, stale),
+    cache_pruned: |removed| format!("{} entrada(s) removida(s)", removed),
+
+    analyzing: |what| format!("Analisando {}...", what),
+    generating_report: |rev| format!("Gerando relatório do commit {}...", rev),
+    analyzing_branch: |head, base| format!("Analisando {} contra {}...", head, base),
+    analyzing_staged: "Analisando alterações staged...",
+    analyzing_worktree: "Analisando alterações não commitadas...",
+    no_commits: "Nenhum commit encontrado",
+    cancelling: "Cancelando as requisições em andamento... (Ctrl-C de novo para sair imediatamente)",
+    internal_failure: |e| format!("falha interna: {}", e),
+    processing_commit: |index, total| format!("Commit {}/{}", index, total),
+    analysis_saved: |file| format!("Análise salva em '{}'", file),
+    commit_skipped: |hash, reason| format!("Commit {}: {}", hash, reason),
+    commit_cached: |hash, file| format!("Commit {} já analisado em '{}' (use --force para refazer)", hash, file),
+    commit_failed: |hash, e| format!("Commit {}: {}", hash, e),
+    no_code_changes: "sem alterações de código detectadas",
+    summary_processed: "Processados:",
+    summary_skipped: "Ignorados:",
+    summary_cached: "Em cache:",
+    summary_errors: "Erros:",
+    summary_not_processed: "Não processados:",
+    summary_categories: "Categorias:",
+    summary_top_risks: |shown, total| format!("Maiores riscos ({} de {} commits):", shown, total),
+    interrupted_by_user: "análise interrompida pelo usuário",
+    analysis_finished: "Análise finalizada!",
+    pending_message: "Alterações ainda não commitadas",
+    no_pending_changes: |kind| format!("Nenhuma alteração {} encontrada", kind),
+    no_branch_changes: |head, base| format!("Nenhuma alteração em '{}' que não esteja em '{}'", head, base),
+    no_staged_changes: "Nenhuma alteração staged encontrada",
+    risk_not_reported: "Modelo não informou o nível de risco",
+    risk_low: "BAIXO",
+    risk_medium: "MÉDIO",
+    risk_high: "ALTO",
+    message_written: |file| format!("Mensagem sugerida escrita em '{}'", file),
+    hook_installed: |name, path| format!("Hook {} instalado em '{}'", name, path),
+    hook_removed: |path| format!("Hook removido de '{}'", path),
+    hook_exists: |path| {
+        format!("já existe um hook em '{}' que não foi instalado por esta ferramenta; remova ou integre manualmente", path)
+    },
+    hook_missing: |path| format!("nenhum hook instalado em '{}'", path),
+    hook_not_ours: |path| format!("o hook em '{}' não foi instalado por esta ferramenta; nada removido", path),
+
+    header_commit: "Commit:",
+    header_message: "Mensagem:",
+    header_author: "Autor:",
+    header_at: "em",
+    header_changes: "Alterações:",
+    header_lines_in_files: |files| format!("linhas em {} arquivo(s)", files),
+    merge_base: "Merge-base:",
+    commits: "Commits:",
+    branch_message: |head, base, count| format!("Branch {} sobre {} ({} commits):", head, base, count),
+    diff_size: "Tamanho do diff:",
+    diff_size_tokens: |tokens, limit| format!("~{} tokens (cabem {} por requisição)", tokens, limit),
+    diff_too_large: "Diff muito grande, dividindo em pedaços...",
+    diff_too_large_message: "Diff muito grande, resumindo antes de gerar a mensagem...",
+    too_many_chunks: |count, max| {
+        format!("Aviso: {} pedaços excedem o máximo de {}. Processando apenas os primeiros {} pedaços.", count, max, max)
+    },
+    split_into: |count| format!("Dividido em {} pedaços", count),
+    chunk_progress: |index, total, tokens| format!("Pedaço {}/{} (~{} tokens)", index, total, tokens),
+    too_many_groups: |count, max| {
+        format!("Aviso: {} grupos de arquivos excedem o máximo de {}. Analisando apenas os primeiros {}.", count, max, max)
+    },
+    files_in_analyses: |files, groups| format!("{} arquivo(s) em {} análise(s)", files, groups),
+    file_progress: |index, total, files, tokens| format!("Arquivo {}/{}: {} (~{} tokens)", index, total, files, tokens),
+    file_too_large: |path| format!("{} não cabe numa requisição, dividindo em pedaços...", path),
+    reducing: |groups, depth| format!("As análises não cabem no contexto, resumindo em {} grupos (nível {})", groups, depth),
+    group_progress: |index, total, depth| format!("Grupo {}/{} do nível {}", index, total, depth),
+    item_chunk: "pedaço",
+    item_file: "arquivo",
+    item_group: "grupo",
+    all_items_failed: |item| format!("Todos os {}s falharam", item),
+    item_failed: |item, index, total, e| format!("Erro no {} {}/{}: {} - continuando...", item, index, total, e),
+    item_error: |item, index, e| format!("**Erro no {} {}:** {}", item, index, e),
+    no_chunks: "Nenhum pedaço de diff encontrado",
+    context_too_small: |context, answer| {
+        format!(
+            "a janela de contexto de {} tokens não comporta o prompt e os {} tokens reservados para a resposta; aumente num_ctx ou reduza answer_tokens",
+            context, answer
+        )
+    },
+    empty_commit_message: "o modelo não retornou uma mensagem de commit",
+    excluded_by_filters: |count| format!("{} arquivo(s) fora do prompt pelos filtros de caminho", count),
+    analyzed_with: |path| format!("*Analisado junto com `{}`.*", path),
+    not_analyzed: "*Não analisado: limite de pedaços atingido.*",
+    placeholder_analysis: |index| format!("(análise {} gerada pelo modelo)", index),
+    unknown_template: |name, names| format!("template desconhecido '{}' (use {})", name, names),
+
+    cancelled: "cancelado pelo usuário",
+    partial_marker: "*[Resposta parcial",
+    sending_request: |attempt, total| format!("Enviando requisição... (tentativa {}/{})", attempt, total),
+    response_received: "Resposta recebida",
+    empty_response: "Resposta vazia do modelo",
+    retrying: |e, delay| format!("{} - tentando novamente em {}ms...", e, delay),
+    all_attempts_failed: "Todas as tentativas falharam",
+    current_thread_runtime: "o Analyzer bloqueia esperando o modelo e não pode rodar dentro de um runtime current_thread do tokio; chame-o de outra thread",
+    echo_response: |chars, lines| format!("[echo] prompt com {} caracteres e {} linhas", chars, lines),
+    context_query_failed: |e| format!("Não foi possível consultar o contexto do modelo: {}", e),
+    num_ctx_capped: |num_ctx, max| format!("num_ctx {} passa do máximo do modelo, usando {}", num_ctx, max),
+    context_window: |tokens| format!("Janela de contexto: {} tokens", tokens),
+    json_error: |e| format!("Erro ao decodificar resposta JSON: {}", e),
+    idle_timeout: |secs| format!("Nenhum token recebido em {}s", secs),
+    connection_error: |e| format!("Erro de conexão: {}", e),
+    ollama_api_error: |e| format!("Erro na API do Ollama: {}", e),
+    openai_api_error: |e| format!("Erro na API compatível com OpenAI: {}", e),
+    stream_interrupted: |e| format!("Stream interrompido: {}", e),
+    stream_ended: "Stream terminou antes do fim da resposta",
+    keeping_partial: |reason, chars| format!("{} - mantendo a resposta parcial ({} caracteres)", reason, chars),
+    structured_fallback: |e| format!("Resposta fora do esquema JSON ({}), usando o texto livre", e),
+    json_missing: "a resposta não tem um objeto JSON",
+    json_empty_purpose: "o campo `purpose` veio vazio",
+    no_choices: "Resposta sem choices",
+
+    query_up_to: |n, range| format!("até {} commits em {}", n, range),
+    query_range: |range| format!("commits em {}", range),
+    query_last: |n| format!("últimos {} commits", n),
+    query_commits: "commits",
+    query_since: |date| format!("desde {}", date),
+    query_until: |date| format!("até {}", date),
+    query_authors: |authors| format!("de {}", authors),
+    query_grep: |patterns| format!("com mensagem contendo {}", patterns),
+    query_paths: |paths| format!("em {}", paths),
+    query_no_merges: "(sem merges)",
+    git_log_failed: |e| format!("git log falhou: {}", e),
+    invalid_revision: |rev| format!("revisão inválida '{}'", rev),
+    unknown_author: "desconhecido",
+    no_merge_base: |base, head| format!("não há merge-base entre '{}' e '{}'", base, head),
+
+    unknown_backend: |name, names| format!("backend desconhecido '{}' (use {})", name, names),
+    unknown_profile: |name, names| format!("perfil desconhecido '{}' (use {})", name, names),
+    unknown_locale: |name, names| format!("idioma desconhecido '{}' (use {})", name, names),
+    read_failed: |path, e| format!("não foi possível ler '{}': {}", path, e),
+    invalid_config: |path, e| format!("configuração inválida em '{}': {}", path, e),
+    invalid_env: |var, value, e| format!("variável {} inválida '{}': {}", var, value, e),
+    empty_model: "o nome do modelo não pode ser vazio",
+    invalid_url_scheme: |scheme| format!("URL do servidor com esquema inválido '{}': use http ou https", scheme),
+    invalid_url: |url, e| format!("URL do servidor inválida '{}': {}", url, e),
+    must_be_positive: |name| format!("{} precisa ser maior que zero", name),
+    answer_tokens_too_large: |answer, num_ctx| format!("answer_tokens ({}) precisa ser menor que num_ctx ({})", answer, num_ctx),
+    repo_not_found: |path| format!("repositório não encontrado: '{}'", path),
+    git_unavailable: |e| format!("não foi possível executar o git: {}", e),
+    not_a_repo: |path| format!("'{}' não é um repositório git", path),
+    invalid_pattern: |pattern, e| format!("padrão de caminho inválido '{}': {}", pattern, e),
+
+    templates_dir_unreadable: |dir, e| format!("não foi possível ler o diretório de templates '{}': {}", dir, e),
+    template_error: |name, e| format!("template '{}': {}", name, e),
+    template_unknown_variable: |name, names| format!("variável desconhecida `{}` (use {})", name, names),
+    template_unclosed_tag: "`{{` sem o `}}` correspondente",
+    template_stray_else: "`{{else}}` fora de um `{{#if}}`",
+    template_stray_end: "`{{/if}}` sem `{{#if}}`",
+    template_unclosed_if: |var| format!("`{{{{#if {}}}}}` sem `{{{{/if}}}}`", var),
+
+    language: "português do Brasil",
+    prompt_stats: |files, insertions, deletions| format!("{} arquivo(s), +{} -{} linhas", files, insertions, deletions),
+    prompt_chunk_item: |index| format!("**Chunk {}:**", index),
+    prompt_part_item: |index| format!("**Parte {}:**", index),
+    yes: "sim",
+    no: "não",
+    risk_section: (
+        "RISCO",
+        "Classifique o risco destas alterações como BAIXO, MÉDIO ou ALTO e justifique em uma frase",
+    ),
+    json_instructions: "Responda APENAS com um objeto JSON, sem texto antes ou depois, com os campos:",
+    json_fields: [
+        "O que esta mudança pretende resolver/implementar",
+        "Como isso afeta o sistema",
+        "Pontos importantes, riscos ou considerações",
+        "Risco de publicar a mudança: `low`, `medium` ou `high`",
+        "Tipos da mudança, entre: `refactor`, `bugfix`, `feature`, `dependency`, `config`, `schema`, `test-only`",
+        "Módulos, pacotes ou camadas afetados",
+        "`true` se a mudança quebra compatibilidade com quem usa o código",
+    ],
+    json_covering: |sections| format!("cobrindo {}", sections),
+    templates: [
+        include_str!("../../templates/pt-BR/analysis.txt"),
+        include_str!("../../templates/pt-BR/chunk.txt"),
+        include_str!("../../templates/pt-BR/summary.txt"),
+        include_str!("../../templates/pt-BR/file_analysis.txt"),
+        include_str!("../../templates/pt-BR/file_synthesis.txt"),
+        include_str!("../../templates/pt-BR/group_summary.txt"),
+        include_str!("../../templates/pt-BR/commit_message.txt"),
+    ],
+    profiles: [
+        ProfileText {
+            description: "propósito e impacto de commits de uma migração",
+            role: "Você é um engenheiro de software sênior especializado em migrações.",
+            task: "Analise este commit e forneça um resumo estruturado explicando:",
+            sections: &[
+                ("PROPÓSITO", "O que esta mudança pretende resolver/implementar"),
+                ("IMPACTO", "Como isso afeta o sistema e a migração"),
+                ("OBSERVAÇÕES", "Pontos importantes, riscos ou considerações"),
+            ],
+            focus: &["Principais alterações", "Propósito específico das mudanças", "Impacto técnico relevante"],
+            default_context: "Migração de Java 8 → 17 e Hibernate 5 → 6",
+            commit_title: "Análise do Commit",
+            branch_title: "Análise da Branch",
+            analysis_heading: "Análise Técnica",
+        },
+        ProfileText {
+            description: "crítica no estilo code review",
+            role: "Você é um revisor de código sênior, criterioso e objetivo.",
+            task: "Revise este commit como num code review e aponte:",
+            sections: &[
+                ("PROBLEMAS", "Bugs, erros de lógica, casos não tratados e regressões prováveis, citando arquivo e linha"),
+                ("QUALIDADE", "Legibilidade, nomes, duplicação e aderência ao estilo do código ao redor"),
+                ("TESTES", "O que deveria ser testado e não está"),
+                ("SUGESTÕES", "Mudanças concretas, da mais para a menos importante"),
+            ],
+            focus: &["Bugs e erros de lógica", "Problemas de legibilidade ou manutenção", "Testes que faltam"],
+            default_context: "",
+            commit_title: "Revisão do Commit",
+            branch_title: "Revisão da Branch",
+            analysis_heading: "Revisão de Código",
+        },
+        ProfileText {
+            description: "vulnerabilidades, superfície de ataque e segredos",
+            role: "Você é um engenheiro de segurança de aplicações revisando alterações de código.",
+            task: "Analise este commit do ponto de vista de segurança e aponte:",
+            sections: &[
+                (
+                    "VULNERABILIDADES",
+                    "Falhas introduzidas ou corrigidas (injeção, autenticação, autorização, criptografia, exposição de dados), com arquivo e linha",
+                ),
+                ("SUPERFÍCIE DE ATAQUE", "Entradas, endpoints, permissões ou dependências novas ou alteradas"),
+                ("SEGREDOS", "Credenciais, chaves ou dados sensíveis presentes no diff"),
+                ("RECOMENDAÇÕES", "Correções e verificações sugeridas, da mais para a menos grave"),
+            ],
+            focus: &[
+                "Entradas não validadas e pontos de injeção",
+                "Mudanças em autenticação, autorização ou criptografia",
+                "Segredos ou dados sensíveis expostos",
+            ],
+            default_context: "",
+            commit_title: "Análise de Segurança do Commit",
+            branch_title: "Análise de Segurança da Branch",
+            analysis_heading: "Análise de Segurança",
+        },
+        ProfileText {
+            description: "entrada de changelog para quem usa o projeto",
+            role: "Você é um mantenedor escrevendo o changelog do projeto para quem o usa.",
+            task: "Escreva a entrada de changelog deste commit, no estilo Keep a Changelog:",
+            sections: &[
+                ("CATEGORIA", "Uma de Added, Changed, Deprecated, Removed, Fixed ou Security"),
+                ("ENTRADA", "Uma ou duas linhas do ponto de vista de quem usa o projeto, sem detalhes de implementação"),
+                ("QUEBRA DE COMPATIBILIDADE", "O que muda para quem atualiza, ou \"nenhuma\""),
+            ],
+            focus: &["Mudanças visíveis para quem usa o projeto", "Correções de comportamento", "Quebras de compatibilidade"],
+            default_context: "",
+            commit_title: "Changelog do Commit",
+            branch_title: "Changelog da Branch",
+            analysis_heading: "Entrada de Changelog",
+        },
+        ProfileText {
+            description: "explicação em linguagem simples para quem chegou no time",
+            role: "Você é um engenheiro experiente explicando o código do projeto para quem acabou de chegar no time.",
+            task: "Explique este commit em linguagem simples, sem pressupor conhecimento do código:",
+            sections: &[
+                ("O QUE MUDOU", "A alteração em poucas frases, sem jargão desnecessário"),
+                ("POR QUE", "O problema ou a necessidade por trás dela"),
+                ("ONDE", "As partes do sistema envolvidas e o papel de cada uma"),
+                ("CONCEITOS", "Termos, padrões ou tecnologias que ajudam a entender a mudança"),
+            ],
+            focus: &["O que o código alterado faz", "Por que provavelmente mudou", "Conceitos necessários para entender o trecho"],
+            default_context: "",
+            commit_title: "Explicação do Commit",
+            branch_title: "Explicação da Branch",
+            analysis_heading: "Explicação",
+        },
+    ],
+
+    file_added: "arquivo novo",
+    file_deleted: "arquivo removido",
+    file_renamed: |old| format!("renomeado de `{}`", old),
+    file_copied: |old| format!("copiado de `{}`", old),
+    similarity: |percent| format!("similaridade {}%", percent),
+    mode_change: |old, new| format!("modo {} → {}", old, new),
+    mode_now: |kind| format!("agora {}", kind),
+    mode_executable: "executável",
+    mode_symlink: "link simbólico",
+    mode_submodule: "submódulo",
+    file_binary: "binário, conteúdo não exibido",
+    file_modified: "modificado",
+
+    file_heading: "Arquivo",
+    commit_info: "Informações do Commit",
+    full_hash: "Hash Completo",
+    short_hash: "Hash Curto",
+    author: "Autor",
+    commit_date: "Data do Commit",
+    files_modified: "Arquivos Modificados",
+    lines_added: "Linhas Adicionadas",
+    lines_removed: "Linhas Removidas",
+    untitled: "Sem título",
+    untitled_file: "sem_titulo",
+    branch_info: "Informações da Branch",
+    details: "Detalhes das Alterações",
+    files: "Arquivos",
+    excluded_files: "Arquivos fora da análise",
+    outside_include: "fora do `include`",
+    per_file_analyses: "Análises por Arquivo",
+    chunks_analyzed: "Pedaços Analisados",
+    reduction_depth: |depth| format!("profundidade da redução: {}", depth),
+    appendix: "Apêndice: Commits",
+    appendix_changes: |insertions, deletions, files| format!("+{} -{} em {} arquivo(s)", insertions, deletions, files),
+    generated_at: "Relatório gerado em",
+    col_file: "Arquivo",
+    col_kind: "Tipo",
+    col_changes: "Alterações",
+    col_reason: "Motivo",
+    col_hash: "Hash",
+    col_date: "Data",
+    col_author: "Autor",
+    col_message: "Mensagem",
+    col_score: "Pontos",
+    col_risk: "Risco",
+    col_categories: "Categorias",
+    risk_score: "Pontuação de Risco",
+    field_purpose: "Propósito",
+    field_impact: "Impacto",
+    field_observations: "Observações",
+    field_risk: "Risco",
+    field_categories: "Categorias",
+    field_components: "Componentes",
+    field_breaking_change: "Quebra compatibilidade",
+};
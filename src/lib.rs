@@ -0,0 +1,34 @@
+//! Extrai diffs de commits do Git e gera análises técnicas com um modelo local.
+//!
+//! O [`Analyzer`] junta as etapas (git → markdown → pedaços → prompt → modelo → relatório)
+//! e pode ser usado por outras ferramentas sem passar pela linha de comando.
+
+pub mod analysis;
+pub mod analyzer;
+pub mod cache;
+pub mod chunk;
+pub mod config;
+pub mod diff;
+pub mod filter;
+pub mod git;
+pub mod hook;
+pub mod i18n;
+pub mod llm;
+pub mod markdown;
+mod native;
+pub mod ollama;
+pub mod openai;
+pub mod profile;
+pub mod prompt;
+pub mod report;
+pub mod risk;
+pub mod template;
+pub mod term;
+#[cfg(test)]
+mod test_support;
+pub mod tokens;
+
+pub use analyzer::{AnalysisKind, AnalysisReport, Analyzer, BranchReport};
+pub use chunk::DiffChunk;
+pub use config::Settings;
+pub use git::{CommitInfo, CommitQuery, PendingChanges};
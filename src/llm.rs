@@ -0,0 +1,294 @@
+use crate::config::{BackendKind, Settings, DEFAULT_NUM_CTX};
+use crate::i18n::{msg, Messages};
+use crate::ollama::{OllamaChat, OllamaGenerate};
+use crate::openai::OpenAiCompatible;
+use crate::term::*;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::runtime::{Handle, Runtime, RuntimeFlavor};
+use tokio::sync::{watch, OwnedSemaphorePermit, Semaphore};
+
+pub type LlmFuture<'a> = Pin<Box<dyn Future<Output = Result<String, Box<dyn std::error::Error>>> + 'a>>;
+
+pub type ContextFuture<'a> = Pin<Box<dyn Future<Output = usize> + 'a>>;
+
+pub type SlotFuture<'a> = Pin<Box<dyn Future<Output = Result<Option<OwnedSemaphorePermit>, Box<dyn std::error::Error>>> + 'a>>;
+
+/// Um servidor de inferência capaz de responder a um prompt.
+///
+/// `complete` faz uma única tentativa; novas tentativas, logs, cancelamento e o limite de
+/// requisições por host ficam com [`call_llm`]. `format` é o esquema JSON da resposta;
+/// backends que não o suportam podem ignorá-lo.
+pub trait LlmBackend: Send + Sync {
+    fn label(&self) -> &'static str;
+    fn complete<'a>(&'a self, prompt: &'a str, format: Option<&'a Value>) -> LlmFuture<'a>;
+    /// Janela de contexto (em tokens) que as requisições vão usar.
+    fn context_window(&self) -> ContextFuture<'_>;
+    /// Espera uma vaga no servidor; ela fica ocupada enquanto a permissão devolvida existir.
+    fn reserve(&self) -> SlotFuture<'_> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// Sinal de cancelamento (Ctrl-C) compartilhado; todos os clones veem o mesmo estado.
+#[derive(Clone)]
+pub struct CancelToken {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        CancelToken {
+            sender: Arc::new(watch::Sender::new(false)),
+        }
+    }
+}
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    pub async fn cancelled(&self) {
+        let mut receiver = self.sender.subscribe();
+        // o sender vive enquanto o token existir, entao o erro nao acontece
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+/// Erro de uma chamada interrompida pelo [`CancelToken`]; reconheça com `e.is::<Cancelled>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(msg().cancelled)
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+// as chamadas ao modelo rodam aqui; quem chama fica bloqueado esperando a resposta
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("falha ao criar o runtime do cliente HTTP")
+    })
+}
+
+// dentro de um runtime do tokio (o `#[tokio::main]` de quem usa a biblioteca) o block_on
+// entraria em panico; no de varias threads a thread e liberada antes de esperar, e no de uma
+// thread so esperar pararia o proprio runtime, entao vira erro
+fn block_on<F: Future>(future: F, m: &Messages) -> Result<F::Output, Box<dyn std::error::Error>> {
+    match Handle::try_current().map(|handle| handle.runtime_flavor()) {
+        Err(_) => Ok(runtime().block_on(future)),
+        Ok(RuntimeFlavor::MultiThread) => Ok(tokio::task::block_in_place(|| runtime().block_on(future))),
+        Ok(_) => Err(m.current_thread_runtime.into()),
+    }
+}
+
+pub fn create_backend(settings: &Settings) -> Result<Box<dyn LlmBackend>, Box<dyn std::error::Error>> {
+    // com streaming quem limita e o tempo sem tokens (idle_timeout_secs), nao a resposta toda
+    let streaming = settings.stream && matches!(settings.backend, BackendKind::Ollama | BackendKind::OllamaChat);
+
+    let mut builder = Client::builder();
+    if !streaming {
+        builder = builder.timeout(Duration::from_secs(settings.timeout_secs));
+    }
+    let client = builder.build()?;
+
+    let backend: Box<dyn LlmBackend> = match settings.backend {
+        BackendKind::Ollama => Box::new(OllamaGenerate::new(client, settings)),
+        BackendKind::OllamaChat => Box::new(OllamaChat::new(client, settings)),
+        BackendKind::Openai => Box::new(OpenAiCompatible::new(client, settings)),
+        BackendKind::Echo => return Ok(Box::new(EchoBackend::new(settings))),
+    };
+
+    Ok(Box::new(HostLimited {
+        inner: backend,
+        limiter: host_limiter(&settings.ollama_url, settings.max_requests_per_host),
+    }))
+}
+
+/// Limitador compartilhado por todos os backends do processo que falam com o mesmo host:porta.
+pub fn host_limiter(url: &str, max: usize) -> Arc<Semaphore> {
+    static LIMITERS: OnceLock<Mutex<HashMap<String, Arc<Semaphore>>>> = OnceLock::new();
+
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| Some(format!("{}:{}", u.host_str()?, u.port_or_known_default()?)))
+        .unwrap_or_else(|| url.to_string());
+
+    LIMITERS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .entry(host)
+        .or_insert_with(|| Arc::new(Semaphore::new(max)))
+        .clone()
+}
+
+// uma vaga do host por tentativa, reservada pelo call_llm antes de mandar a requisicao
+struct HostLimited {
+    inner: Box<dyn LlmBackend>,
+    limiter: Arc<Semaphore>,
+}
+
+impl LlmBackend for HostLimited {
+    fn label(&self) -> &'static str {
+        self.inner.label()
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str, format: Option<&'a Value>) -> LlmFuture<'a> {
+        self.inner.complete(prompt, format)
+    }
+
+    fn context_window(&self) -> ContextFuture<'_> {
+        self.inner.context_window()
+    }
+
+    fn reserve(&self) -> SlotFuture<'_> {
+        Box::pin(async move { Ok(Some(self.limiter.clone().acquire_owned().await?)) })
+    }
+}
+
+pub fn context_window(backend: &dyn LlmBackend, m: &Messages) -> Result<usize, Box<dyn std::error::Error>> {
+    block_on(backend.context_window(), m)
+}
+
+pub fn call_llm(
+    backend: &dyn LlmBackend,
+    settings: &Settings,
+    cancel: &CancelToken,
+    prompt: &str,
+    format: Option<&Value>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let label = backend.label();
+    let m = settings.locale.messages();
+
+    let attempts = async {
+        for attempt in 1..=settings.max_retries {
+            // o log sai so com a vaga do host na mao, quando a requisicao vai mesmo
+            let slot = tokio::select! {
+                slot = backend.reserve() => slot?,
+                _ = cancel.cancelled() => return Err(Cancelled.into()),
+            };
+            if cancel.is_cancelled() {
+                return Err(Cancelled.into());
+            }
+            println!("{}[{}]{} {}", COLOR_BLUE, label, COLOR_RESET, (m.sending_request)(&attempt, &settings.max_retries));
+
+            // descartar o futuro fecha a conexao, o que interrompe a geracao no servidor
+            let result = tokio::select! {
+                result = backend.complete(prompt, format) => result,
+                _ = cancel.cancelled() => return Err(Cancelled.into()),
+            };
+            // a espera antes de tentar de novo nao segura a vaga
+            drop(slot);
+
+            let error_msg = match result {
+                Ok(response) if !response.trim().is_empty() => {
+                    println!("{}[{}]{} {}", COLOR_GREEN, label, COLOR_RESET, m.response_received);
+                    return Ok(response);
+                }
+                Ok(_) => m.empty_response.to_string(),
+                Err(e) => e.to_string(),
+            };
+
+            if attempt < settings.max_retries {
+                println!("{}[{}]{} {}", COLOR_YELLOW, label, COLOR_RESET, (m.retrying)(&error_msg, &settings.retry_delay_ms));
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_millis(settings.retry_delay_ms)) => continue,
+                    _ = cancel.cancelled() => return Err(Cancelled.into()),
+                }
+            }
+            return Err(error_msg.into());
+        }
+
+        Err(m.all_attempts_failed.into())
+    };
+    block_on(attempts, m)?
+}
+
+pub fn clean_response(response: String) -> String {
+    let mut cleaned = response;
+    
+    let think_patterns = [
+        "</think>",
+        "<think>",
+        "</thinking>",
+        "<thinking>",
+    ];
+    
+    for pattern in &think_patterns {
+        if let Some(pos) = cleaned.find(pattern) {
+            if pattern.starts_with("</") {
+                let content_start = pos + pattern.len();
+                cleaned = cleaned[content_start..].trim_start().to_string();
+            } else {
+                cleaned = cleaned[..pos].trim_end().to_string();
+            }
+        }
+    }
+    
+    cleaned.trim().to_string()
+}
+
+// resposta deterministica, pra testar o pipeline sem servidor
+pub struct EchoBackend {
+    pub context_window: usize,
+    pub messages: &'static Messages,
+}
+
+impl EchoBackend {
+    pub fn new(settings: &Settings) -> Self {
+        EchoBackend {
+            context_window: settings.num_ctx.unwrap_or(DEFAULT_NUM_CTX),
+            messages: settings.locale.messages(),
+        }
+    }
+}
+
+impl LlmBackend for EchoBackend {
+    fn label(&self) -> &'static str {
+        LABEL_ECHO
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str, format: Option<&'a Value>) -> LlmFuture<'a> {
+        let first_line = prompt.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        let summary = (self.messages.echo_response)(&prompt.chars().count(), &prompt.lines().count());
+        // com esquema devolve um JSON valido, pra testar tambem o relatorio estruturado
+        let response = match format {
+            Some(_) => serde_json::json!({
+                "purpose": summary,
+                "impact": first_line,
+                "observations": "",
+                "risk_level": "low",
+                "categories": [],
+                "components": [],
+                "breaking_change": false
+            })
+            .to_string(),
+            None => format!("{}\n\n{}", summary, first_line),
+        };
+        Box::pin(async move { Ok(response) })
+    }
+
+    fn context_window(&self) -> ContextFuture<'_> {
+        let context_window = self.context_window;
+        Box::pin(async move { context_window })
+    }
+}
@@ -1,643 +1,487 @@
-use chrono::prelude::*;
-use regex::Regex;
-use reqwest::blocking::Client;
-use serde::{Deserialize, Serialize};
-use std::env;
-use std::fmt::Write as FmtWrite;
-use std::process::Command;
-use std::time::Duration;
-use std::thread;
-
-const REPO_PATH: &str = ""; // caminho do repo
-const OLLAMA_MODEL: &str = "gemma3:4b-it-q8_0"; // modelo pra usar
-const OLLAMA_API_URL: &str = "http://localhost:11434/api/generate";
-const MAX_DIFF_SIZE: usize = 3000; // maximo do diff
-const CHUNK_SIZE: usize = 3000; // tamanho dos pedacos
-const MAX_RETRIES: u32 = 3; // tentativas para Ollama
-const RETRY_DELAY_MS: u64 = 1000; // delay entre tentativas
-const OLLAMA_TIMEOUT_SECS: u64 = 600; // timeout para Ollama em segundos
-const OLLAMA_MAX_CHUNKS: usize = 100; // maximo de pedacos para dividir o diff
-
-// Códigos de cores ANSI
-const COLOR_RESET: &str = "\x1b[0m";
-const COLOR_CYAN: &str = "\x1b[1;36m";
-const COLOR_BLUE: &str = "\x1b[1;34m";
-const COLOR_YELLOW: &str = "\x1b[1;33m";
-const COLOR_GREEN: &str = "\x1b[1;32m";
-const COLOR_RED: &str = "\x1b[1;31m";
-const COLOR_WHITE: &str = "\x1b[1;37m";
-const COLOR_MAGENTA: &str = "\x1b[1;35m";
-const COLOR_GRAY: &str = "\x1b[1;90m";
-
-// Etiquetas de log
-const LABEL_INFO: &str = "INFO";
-const LABEL_REPO: &str = "REPO";
-const LABEL_MODELO: &str = "MODELO";
-const LABEL_PROCESSANDO: &str = "PROCESSANDO";
-const LABEL_SUCESSO: &str = "SUCESSO";
-const LABEL_IGNORADO: &str = "IGNORADO";
-const LABEL_ERRO: &str = "ERRO";
-const LABEL_RESUMO: &str = "RESUMO";
-const LABEL_CONCLUIDO: &str = "CONCLUÍDO";
-const LABEL_CHUNK: &str = "CHUNK";
-const LABEL_PROC: &str = "PROC";
-const LABEL_OLLAMA: &str = "OLLAMA";
-
-// Separador
-const SEPARATOR: &str = "────────────────────────────────────────────────────────────";
-
-#[derive(Serialize)]
-struct OllamaRequest<'a> {
-    model: &'a str,
-    prompt: String,
-    stream: bool,
+mod cli;
+
+use cli::{CacheCommands, Cli, Commands, ConfigCommands, SelectionArgs};
+use git_diff_extractor_rust::cache::{AnalysisCache, CacheKey};
+use git_diff_extractor_rust::config::{self, LoadedSettings};
+use git_diff_extractor_rust::i18n::{self, msg};
+use git_diff_extractor_rust::llm::Cancelled;
+use git_diff_extractor_rust::analysis::RiskLevel;
+use git_diff_extractor_rust::report::write_atomic;
+use git_diff_extractor_rust::risk::{self, CommitRisk};
+use git_diff_extractor_rust::term::*;
+use git_diff_extractor_rust::{git, hook, Analyzer, CommitQuery, PendingChanges, Settings};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::{JoinError, JoinHandle};
+
+// quantos commits aparecem na tabela de risco do resumo
+const RISK_RANKING_SIZE: usize = 5;
+const RISK_MESSAGE_WIDTH: usize = 50;
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}[{}]{} {}", COLOR_RED, msg().label_error, COLOR_RESET, e);
+        std::process::exit(1);
+    }
 }
 
-#[derive(Deserialize)]
-struct OllamaResponse {
-    response: String,
-}
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    // o idioma da ajuda do clap vem do que der pra ler antes dele; erros aqui ficam pro load de baixo
+    let args: Vec<String> = std::env::args().collect();
+    if let Ok(early) = config::load(&cli::early_settings(&args)) {
+        i18n::set_locale(early.settings.locale);
+    }
 
-#[derive(Debug, Clone)]
-struct CommitInfo {
-    hash: String,
-    short_hash: String,
-    message: String,
-    author: String,
-    date: String,
-    files_changed: Vec<String>,
-    insertions: u32,
-    deletions: u32,
+    let cli = Cli::parse_localized(msg());
+    let loaded = config::load(&cli.settings.to_partial())?;
+    i18n::set_locale(loaded.settings.locale);
+
+    // sem subcomando continua aceitando `cargo run 5`
+    let command = cli.command.unwrap_or(Commands::Analyze {
+        count: Some(cli.count.unwrap_or(10)),
+        force: false,
+        selection: SelectionArgs::default(),
+    });
+
+    match command {
+        Commands::Config { action } => run_config(&action, &loaded),
+        Commands::Cache { action } => run_cache(&action, &loaded.settings),
+        Commands::Staged { worktree, message, output } => {
+            let pending = if worktree { PendingChanges::WorkingTree } else { PendingChanges::Staged };
+            run_pending(&loaded.settings, pending, message, output)
+        }
+        Commands::Pr { base, head, output } => run_pr(&loaded.settings, &base, &head, output),
+        Commands::Message { conventional } => run_message(&loaded.settings, conventional),
+        Commands::PrepareCommitMsg { file, source, sha: _, conventional } => {
+            run_prepare_commit_msg(&loaded.settings, &file, source.as_deref(), conventional)
+        }
+        Commands::InstallHook { conventional, uninstall } => run_install_hook(&loaded.settings, conventional, uninstall),
+        Commands::Render { rev, template, staged } => run_render(&loaded.settings, &rev, &template, staged),
+        command => run_analysis(&command, &loaded.settings),
+    }
 }
 
-#[derive(Debug)]
-struct DiffChunk {
-    content: String,
-    files: Vec<String>,
-    size: usize,
+// so o prompt na saida, pra poder redirecionar
+fn run_render(settings: &Settings, rev: &str, template: &str, staged: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let analyzer = Analyzer::new(settings.clone())?;
+    let prompt = analyzer.render_prompt((!staged).then_some(rev), template)?;
+
+    // um `| head` que fecha a saida antes do fim nao e erro
+    match writeln!(std::io::stdout(), "{}", prompt) {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(()),
+    }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let num_commits: i32 = env::args()
-        .nth(1)
-        .unwrap_or_else(|| "10".to_string())
-        .parse()?;
+fn run_config(action: &ConfigCommands, loaded: &LoadedSettings) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        ConfigCommands::Show => {
+            let m = msg();
+            println!("{}[{}]{}", COLOR_CYAN, m.label_config, COLOR_RESET);
+            for key in config::KEYS {
+                println!("  {:<21} = {:<30} {}({}){}",
+                         key, loaded.settings.value_of(key), COLOR_GRAY, loaded.source_of(key), COLOR_RESET);
+            }
 
-    println!("{}[{}]{} Analisando os últimos {} commits...", COLOR_CYAN, LABEL_INFO, COLOR_RESET, num_commits);
-    println!("{}[{}]{} {}", COLOR_BLUE, LABEL_REPO, COLOR_RESET, REPO_PATH);
-    println!("{}[{}]{} {}", COLOR_YELLOW, LABEL_MODELO, COLOR_RESET, OLLAMA_MODEL);
-    println!("{}", SEPARATOR);
+            println!("\n{}{}{}", COLOR_WHITE, m.config_files, COLOR_RESET);
+            if let Some(path) = config::user_config_path() {
+                let status = if loaded.files.contains(&path) { m.config_read } else { m.config_not_found };
+                println!("  {:<12} {} {}({}){}", m.config_user, path.display(), COLOR_GRAY, status, COLOR_RESET);
+            }
+            let repo_file = loaded.files.iter().find(|f| f.ends_with(config::REPO_CONFIG_FILE));
+            match repo_file {
+                Some(path) => println!("  {:<12} {} {}({}){}", m.config_repo, path.display(), COLOR_GRAY, m.config_read, COLOR_RESET),
+                None => println!("  {:<12} {} {}({}){}", m.config_repo, config::REPO_CONFIG_FILE, COLOR_GRAY, m.config_not_found, COLOR_RESET),
+            }
 
-    let log_output = Command::new("git")
-        .arg("log")
-        .arg(format!("-n{}", num_commits))
-        .arg("--pretty=format:%H")
-        .current_dir(REPO_PATH)
-        .output()?;
-    
-    let hashes: Vec<String> = String::from_utf8(log_output.stdout)?
-        .lines()
-        .map(String::from)
-        .collect();
+            if let Err(e) = loaded.settings.validate() {
+                println!("\n{}[{}]{} {}", COLOR_YELLOW, m.label_error, COLOR_RESET, e);
+            }
+            Ok(())
+        }
+    }
+}
 
-    let http_client = Client::builder()
-        .timeout(Duration::from_secs(OLLAMA_TIMEOUT_SECS))
-        .build()?;
+fn run_cache(action: &CacheCommands, settings: &Settings) -> Result<(), Box<dyn std::error::Error>> {
+    settings.validate()?;
+    let cache = AnalysisCache::open(&settings.repo_path)?;
+    let m = msg();
 
-    let mut processed = 0;
-    let mut skipped = 0;
-    let mut errors = 0;
+    match action {
+        CacheCommands::Show => {
+            println!("{}[{}]{} {}", COLOR_CYAN, m.label_cache, COLOR_RESET, cache.dir().display());
 
-    for (index, hash) in hashes.iter().enumerate() {
-        println!("\n{}[{}]{} Commit {}/{}", COLOR_GREEN, LABEL_PROCESSANDO, COLOR_RESET, index + 1, hashes.len());
-        
-        match process_commit(&http_client, hash) {
-            Ok(ProcessResult::Success(filename)) => {
-                println!("{}[{}]{} Análise salva em '{}'", COLOR_GREEN, LABEL_SUCESSO, COLOR_RESET, filename);
-                processed += 1;
+            let entries = cache.entries()?;
+            if entries.is_empty() {
+                println!("  {}", m.cache_empty);
+                return Ok(());
             }
-            Ok(ProcessResult::Skipped(reason)) => {
-                println!("{}[{}]{} {}", COLOR_YELLOW, LABEL_IGNORADO, COLOR_RESET, reason);
-                skipped += 1;
-            }
-            Err(e) => {
-                println!("{}[{}]{} Commit {}: {}", COLOR_RED, LABEL_ERRO, COLOR_RESET, &hash[..12], e);
-                errors += 1;
+
+            let mut stale = 0;
+            for stored in &entries {
+                let Some(entry) = &stored.entry else {
+                    println!("  {}{} {}{}", COLOR_RED, stored.path.display(), m.cache_unreadable, COLOR_RESET);
+                    stale += 1;
+                    continue;
+                };
+                let current = entry.key.same_settings(&CacheKey::new(&entry.key.commit, settings));
+                if !current {
+                    stale += 1;
+                }
+                println!("  {} {} {:<24} {} {}{}{}",
+                         git::short_hash(&entry.key.commit), entry.created_at, entry.key.model, entry.report_file.display(),
+                         COLOR_GRAY, if current { "" } else { m.cache_stale }, COLOR_RESET);
             }
+            println!("\n{}", (m.cache_summary)(&entries.len(), &stale));
+        }
+        CacheCommands::Prune { all } => {
+            let removed = cache.prune(&settings.repo_path, settings, *all)?;
+            println!("{}[{}]{} {}", COLOR_GREEN, m.label_cache, COLOR_RESET, (m.cache_pruned)(&removed));
         }
     }
-
-    println!("\n{}", SEPARATOR);
-    println!("{}[{}]{}", COLOR_CYAN, LABEL_RESUMO, COLOR_RESET);
-    println!("  {}Processados:{} {}", COLOR_GREEN, COLOR_RESET, processed);
-    println!("  {}Ignorados:{} {}", COLOR_YELLOW, COLOR_RESET, skipped);
-    println!("  {}Erros:{} {}", COLOR_RED, COLOR_RESET, errors);
-    println!("{}[{}]{} Análise finalizada!", COLOR_CYAN, LABEL_CONCLUIDO, COLOR_RESET);
-    
     Ok(())
 }
 
-#[derive(Debug)]
-enum ProcessResult {
-    Success(String),
-    Skipped(String),
-}
-
-fn process_commit(client: &Client, hash: &str) -> Result<ProcessResult, Box<dyn std::error::Error>> {
-    let commit_info = get_commit_info(hash, REPO_PATH)?;
-    let raw_diff = get_commit_diff(hash, REPO_PATH)?;
-    
-    println!("{}Mensagem:{} {}", COLOR_WHITE, COLOR_RESET, commit_info.message);
-    println!("{}Autor:{} {} {}em{} {}", COLOR_MAGENTA, COLOR_RESET, commit_info.author, COLOR_GRAY, COLOR_RESET, commit_info.date);
-    println!("{}Alterações:{} +{} {}-{}{} linhas em {} arquivo(s)", 
-             COLOR_GREEN, COLOR_RESET, commit_info.insertions, COLOR_RED, commit_info.deletions, COLOR_RESET, commit_info.files_changed.len());
-    
-    // checa se tem mudanca
-    if !raw_diff.lines().any(|l| l.starts_with('+') || l.starts_with('-')) {
-        return Ok(ProcessResult::Skipped("sem alterações de código detectadas".to_string()));
-    }
-
-    let formatted_diff = format_diff_as_markdown(&raw_diff);
-    let diff_size = formatted_diff.chars().count();
-    
-    println!("{}Tamanho do diff:{} {} caracteres", COLOR_GRAY, COLOR_RESET, diff_size);
+fn run_analysis(command: &Commands, settings: &Settings) -> Result<(), Box<dyn std::error::Error>> {
+    let analyzer = Arc::new(Analyzer::new(settings.clone())?);
+    let cache = Arc::new(AnalysisCache::open(&settings.repo_path)?);
+    let m = msg();
 
-    // processa o diff grande ou normal
-    let analysis = if diff_size > MAX_DIFF_SIZE {
-        println!("{}[{}]{} Diff muito grande, dividindo em pedaços...", COLOR_YELLOW, LABEL_CHUNK, COLOR_RESET);
-        process_large_diff(client, &commit_info, &formatted_diff)?
-    } else {
-        let analysis_prompt = build_analysis_prompt(&commit_info.message, &formatted_diff);
-        call_ollama(client, analysis_prompt)?
+    let (description, target, force) = match command {
+        Commands::Analyze { count, force, selection } => {
+            let default_count = if selection.has_date_filter() { None } else { Some(10) };
+            let query = selection.to_query(None, count.or(default_count));
+            ((m.analyzing)(&query.describe()), CommitTarget::Query(query), *force)
+        }
+        Commands::Range { range, count, force, selection } => {
+            let query = selection.to_query(Some(range), *count);
+            ((m.analyzing)(&query.describe()), CommitTarget::Query(query), *force)
+        }
+        Commands::Report { rev, force } => {
+            ((m.generating_report)(rev), CommitTarget::Single(rev.clone()), *force)
+        }
+        _ => unreachable!("tratado em run"),
     };
 
-    let clean_analysis = clean_ollama_response(analysis);
-
-    let final_document = generate_final_document(&commit_info, &clean_analysis, &formatted_diff);
+    print_run_header(settings, &description);
 
-    let filename = generate_filename(&commit_info.message);
-    std::fs::write(&filename, final_document)?;
-    
-    Ok(ProcessResult::Success(filename))
-}
+    let hashes = match target {
+        CommitTarget::Query(query) => git::list_commits(&settings.repo_path, &query)?,
+        CommitTarget::Single(rev) => vec![git::resolve_commit(&settings.repo_path, &rev)?],
+    };
 
-fn process_large_diff(client: &Client, commit_info: &CommitInfo, diff: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let mut chunks = split_diff_into_chunks(diff);
-    let mut analyses = Vec::new();
-    if chunks.is_empty() {
-        return Err("Nenhum pedaço de diff encontrado".into());
-    }
-    if chunks.len() > OLLAMA_MAX_CHUNKS {
-        println!("{}[{}]{} Aviso: {} pedaços excedem o máximo de {}. Processando apenas os primeiros {} pedaços.", 
-                 COLOR_YELLOW, LABEL_CHUNK, COLOR_RESET, chunks.len(), OLLAMA_MAX_CHUNKS, OLLAMA_MAX_CHUNKS);
-        chunks.truncate(OLLAMA_MAX_CHUNKS);
-    }  
-
-    println!("{}[{}]{} Dividido em {} pedaços", COLOR_YELLOW, LABEL_CHUNK, COLOR_RESET, chunks.len());
-    
-    for (i, chunk) in chunks.iter().enumerate() {
-        println!("{}[{}]{} Pedaço {}/{}", COLOR_MAGENTA, LABEL_PROC, COLOR_RESET, i + 1, chunks.len());
-        
-        let chunk_prompt = build_chunk_analysis_prompt(&commit_info.message, &chunk.content, i + 1, chunks.len());
-        
-        match call_ollama(client, chunk_prompt) {
-            Ok(chunk_analysis) => {
-                analyses.push(clean_ollama_response(chunk_analysis));
-            }
-            Err(e) => {
-                println!("{}[{}]{} Erro no pedaço {}/{}: {} - continuando...", 
-                         COLOR_YELLOW, LABEL_ERRO, COLOR_RESET, i + 1, chunks.len(), e);
-                analyses.push(format!("**Erro no pedaço {}:** {}", i + 1, e));
-            }
-        }
-    }
-    
-    if analyses.is_empty() {
-        return Err("Todos os pedaços falharam".into());
+    if hashes.is_empty() {
+        println!("{}[{}]{} {}", COLOR_YELLOW, m.label_skipped, COLOR_RESET, m.no_commits);
+        return Ok(());
     }
-    
-    let combined_prompt = build_summary_prompt(&commit_info.message, &analyses);
-    let final_analysis = call_ollama(client, combined_prompt)?;
-    
-    Ok(clean_ollama_response(final_analysis))
-}
 
-fn split_diff_into_chunks(diff: &str) -> Vec<DiffChunk> {
-    let mut chunks = Vec::new();
-    let mut current_chunk = String::new();
-    let mut current_files = Vec::new();
-    let mut current_size = 0;
-    
-    let file_header_re = Regex::new(r"^### Arquivo: `([^`]+)`").unwrap();
-    
-    for line in diff.lines() {
-        let line_size = line.len() + 1;
-        
-        // se ficou muito grande, cria novo chunk
-        if current_size + line_size > CHUNK_SIZE && !current_chunk.is_empty() {
-            chunks.push(DiffChunk {
-                content: current_chunk.clone(),
-                files: current_files.clone(),
-                size: current_size,
-            });
-            
-            current_chunk.clear();
-            current_files.clear();
-            current_size = 0;
-        }
-        
-        // pega nome do arquivo
-        if let Some(caps) = file_header_re.captures(line) {
-            let file_path = caps.get(1).map_or("", |m| m.as_str());
-            if !current_files.contains(&file_path.to_string()) {
-                current_files.push(file_path.to_string());
+    let mut processed = 0;
+    let mut skipped = 0;
+    let mut cached = 0;
+    let mut errors = 0;
+    let mut not_processed = Vec::new();
+    let mut risks = Vec::new();
+
+    let cancel = analyzer.cancel_token();
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let interrupt = cancel.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                interrupt.cancel();
+                println!("\n{}[{}]{} {}", COLOR_YELLOW, m.label_interrupted, COLOR_RESET, m.cancelling);
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    std::process::exit(130);
+                }
             }
-        }
-        
-        current_chunk.push_str(line);
-        current_chunk.push('\n');
-        current_size += line_size;
-    }
-    
-    // adiciona ultimo chunk
-    if !current_chunk.is_empty() {
-        chunks.push(DiffChunk {
-            content: current_chunk,
-            files: current_files,
-            size: current_size,
         });
-    }
-    
-    chunks
-}
 
-fn get_commit_info(hash: &str, repo_path: &str) -> Result<CommitInfo, Box<dyn std::error::Error>> {
-    let output = Command::new("git")
-        .arg("show")
-        .arg("-s")
-        .arg("--pretty=format:%s%n%an%n%ad%n%b")
-        .arg("--date=format:%Y-%m-%d %H:%M")
-        .arg(hash)
-        .current_dir(repo_path)
-        .output()?;
-
-    if !output.status.success() {
-        return Err(String::from_utf8(output.stderr)?.into());
-    }
-    
-    let output_str = String::from_utf8(output.stdout)?;
-    let lines: Vec<&str> = output_str.lines().collect();
-    
-    let message = lines.get(0).unwrap_or(&"").to_string();
-    let author = lines.get(1).unwrap_or(&"").to_string();
-    let date = lines.get(2).unwrap_or(&"").to_string();
-    
-    let stats_output = Command::new("git")
-        .arg("show")
-        .arg("--stat")
-        .arg("--format=")
-        .arg(hash)
-        .current_dir(repo_path)
-        .output()?;
-    
-    let stats_str = String::from_utf8(stats_output.stdout)?;
-    let (files_changed, insertions, deletions) = parse_git_stats(&stats_str);
-    
-    Ok(CommitInfo {
-        hash: hash.to_string(),
-        short_hash: hash[..12].to_string(),
-        message,
-        author,
-        date,
-        files_changed,
-        insertions,
-        deletions,
-    })
-}
+        let handles = spawn_commits(analyzer, cache, &hashes, settings.jobs, force);
+
+        // espera na ordem dos commits, mesmo que os seguintes terminem antes
+        for (hash, handle) in hashes.iter().zip(handles) {
+            let result = match handle.await {
+                Ok(Ok(result)) => result,
+                Ok(Err(e)) | Err(e) => Err((m.internal_failure)(&e)),
+            };
 
-fn parse_git_stats(stats: &str) -> (Vec<String>, u32, u32) {
-    let mut files = Vec::new();
-    let mut insertions = 0;
-    let mut deletions = 0;
-    
-    for line in stats.lines() {
-        if line.contains("|") {
-            if let Some(file_part) = line.split('|').next() {
-                let file = file_part.trim().to_string();
-                if !file.is_empty() {
-                    files.push(file);
+            match result {
+                Ok(ProcessResult::Success(filename, risk)) => {
+                    println!("{}[{}]{} {}", COLOR_GREEN, m.label_success, COLOR_RESET, (m.analysis_saved)(&filename));
+                    processed += 1;
+                    risks.push(risk);
+                }
+                Ok(ProcessResult::Skipped(reason)) => {
+                    println!("{}[{}]{} {}", COLOR_YELLOW, m.label_skipped, COLOR_RESET, (m.commit_skipped)(&git::short_hash(hash), &reason));
+                    skipped += 1;
+                }
+                Ok(ProcessResult::Cached(filename, risk)) => {
+                    println!("{}[{}]{} {}", COLOR_BLUE, m.label_cache, COLOR_RESET, (m.commit_cached)(&git::short_hash(hash), &filename));
+                    cached += 1;
+                    risks.push(risk);
+                }
+                Ok(ProcessResult::NotStarted | ProcessResult::Cancelled) => not_processed.push(hash),
+                Err(e) => {
+                    println!("{}[{}]{} {}", COLOR_RED, m.label_error, COLOR_RESET, (m.commit_failed)(&git::short_hash(hash), &e));
+                    errors += 1;
                 }
-            }
-        } else if line.contains("insertion") || line.contains("deletion") {
-            let re = Regex::new(r"(\d+) insertion").unwrap();
-            if let Some(caps) = re.captures(line) {
-                insertions = caps.get(1).unwrap().as_str().parse().unwrap_or(0);
-            }
-            
-            let re = Regex::new(r"(\d+) deletion").unwrap();
-            if let Some(caps) = re.captures(line) {
-                deletions = caps.get(1).unwrap().as_str().parse().unwrap_or(0);
             }
         }
-    }
-    
-    (files, insertions, deletions)
-}
-fn build_analysis_prompt(message: &str, diff: &str) -> String {
-    format!(
-        "Você é um engenheiro de software sênior especializado em migrações de Java e Hibernate.
-
-CONTEXTO: Migração de Java 8 → 17 e Hibernate 5 → 6
+    });
 
-TAREFA: Analise este commit e forneça um resumo estruturado explicando:
-1. **PROPÓSITO**: O que esta mudança pretende resolver/implementar
-2. **IMPACTO**: Como isso afeta o sistema e a migração
-3. **OBSERVAÇÕES**: Pontos importantes, riscos ou considerações
+    println!("\n{}", SEPARATOR);
+    println!("{}[{}]{}", COLOR_CYAN, m.label_summary, COLOR_RESET);
+    println!("  {}{}{} {}", COLOR_GREEN, m.summary_processed, COLOR_RESET, processed);
+    println!("  {}{}{} {}", COLOR_YELLOW, m.summary_skipped, COLOR_RESET, skipped);
+    println!("  {}{}{} {}", COLOR_BLUE, m.summary_cached, COLOR_RESET, cached);
+    println!("  {}{}{} {}", COLOR_RED, m.summary_errors, COLOR_RESET, errors);
+    print_risk_ranking(&mut risks);
+
+    if cancel.is_cancelled() {
+        println!("  {}{}{} {}", COLOR_GRAY, m.summary_not_processed, COLOR_RESET, not_processed.len());
+        for hash in &not_processed {
+            let message = git::get_commit_info(hash, &settings.repo_path).map(|c| c.message).unwrap_or_default();
+            println!("    {} {}", git::short_hash(hash), message);
+        }
+        return Err(m.interrupted_by_user.into());
+    }
 
-Seja conciso mas informativo. Use linguagem técnica apropriada.
+    println!("{}[{}]{} {}", COLOR_CYAN, m.label_done, COLOR_RESET, m.analysis_finished);
+    Ok(())
+}
 
---- MENSAGEM DO COMMIT ---
-{}
+// categorias do intervalo e os commits de maior risco primeiro
+fn print_risk_ranking(risks: &mut [CommitRisk]) {
+    if risks.is_empty() {
+        return;
+    }
+    let m = msg();
 
---- DIFF DO CÓDIGO ---
-{}
+    let counts: Vec<String> = risk::category_counts(risks)
+        .iter()
+        .map(|(category, count)| format!("{} {}", category.name(), count))
+        .collect();
+    if !counts.is_empty() {
+        println!("  {}{}{} {}", COLOR_MAGENTA, m.summary_categories, COLOR_RESET, counts.join(", "));
+    }
 
---- ANÁLISE ---",
-        message, diff
-    )
+    risk::rank(risks);
+    let shown = risks.len().min(RISK_RANKING_SIZE);
+    println!("\n  {}{}{}", COLOR_WHITE, (m.summary_top_risks)(&shown, &risks.len()), COLOR_RESET);
+    let ranked = &risks[..shown];
+    let width = ranked.iter().map(|r| r.category_names().chars().count()).chain([m.col_categories.chars().count()]).max().unwrap_or(0);
+    println!("  {}{:>3}  {:<6}  {:<6}  {:<12}  {:<width$}  {}{}",
+             COLOR_GRAY, "#", m.col_score, m.col_risk, m.col_hash, m.col_categories, m.col_message, COLOR_RESET);
+    for (i, risk) in ranked.iter().enumerate() {
+        let color = match risk.level {
+            Some(RiskLevel::High) => COLOR_RED,
+            Some(RiskLevel::Medium) => COLOR_YELLOW,
+            Some(RiskLevel::Low) => COLOR_GREEN,
+            None => COLOR_GRAY,
+        };
+        let message: String = risk.message.chars().take(RISK_MESSAGE_WIDTH).collect();
+        println!("  {:>3}  {:<6}  {}{:<6}{}  {:<12}  {:<width$}  {}",
+                 i + 1, risk.score, color, risk.level.map_or("—", |l| l.label(m)), COLOR_RESET,
+                 risk.short_hash, risk.category_names(), message);
+    }
 }
 
-fn build_chunk_analysis_prompt(message: &str, chunk: &str, chunk_num: usize, total_chunks: usize) -> String {
-    format!(
-        "Você é um engenheiro de software sênior analisando parte de um commit grande.
+fn print_run_header(settings: &Settings, description: &str) {
+    let m = msg();
+    println!("{}[{}]{} {}", COLOR_CYAN, m.label_info, COLOR_RESET, description);
+    println!("{}[{}]{} {}", COLOR_BLUE, m.label_repo, COLOR_RESET, settings.repo_path.display());
+    println!("{}[{}]{} {}", COLOR_YELLOW, m.label_model, COLOR_RESET, settings.model);
+    println!("{}", SEPARATOR);
+}
 
-CONTEXTO: Migração Java 8→17 e Hibernate 5→6
-CHUNK: {}/{} do commit
+fn run_pending(
+    settings: &Settings,
+    pending: PendingChanges,
+    message: Option<String>,
+    output: Option<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let analyzer = Analyzer::new(settings.clone())?;
+    let m = msg();
+
+    let description = match pending {
+        PendingChanges::Staged => m.analyzing_staged,
+        PendingChanges::WorkingTree => m.analyzing_worktree,
+    };
+    print_run_header(settings, description);
 
-TAREFA: Analise APENAS este trecho e identifique:
-- Principais alterações neste chunk
-- Propósito específico das mudanças
-- Impacto técnico relevante
+    let message = message.unwrap_or_else(|| m.pending_message.to_string());
+    let Some(report) = analyzer.analyze_pending(pending, &message)? else {
+        println!("{}[{}]{} {}", COLOR_YELLOW, m.label_skipped, COLOR_RESET, (m.no_pending_changes)(&pending.label()));
+        return Ok(());
+    };
 
-Seja conciso. Este é apenas um fragmento de um commit maior.
+    println!("\n{}", SEPARATOR);
+    println!("{}\n", report.analysis);
+    println!("{}", SEPARATOR);
 
---- MENSAGEM DO COMMIT ---
-{}
+    match report.risk_level() {
+        Some(level) => {
+            let color = match level {
+                RiskLevel::High => COLOR_RED,
+                RiskLevel::Medium => COLOR_YELLOW,
+                RiskLevel::Low => COLOR_GREEN,
+            };
+            println!("{}[{}]{} {}", color, m.label_risk, COLOR_RESET, level.label(m));
+        }
+        None => println!("{}[{}]{} {}", COLOR_GRAY, m.label_risk, COLOR_RESET, m.risk_not_reported),
+    }
 
---- CHUNK DO DIFF ---
-{}
+    if let Some(path) = output {
+        write_atomic(&path, &report.to_markdown())?;
+        println!("{}[{}]{} {}", COLOR_GREEN, m.label_success, COLOR_RESET, (m.analysis_saved)(&path.display()));
+    }
 
---- ANÁLISE DO CHUNK ---",
-        chunk_num, total_chunks, message, chunk
-    )
+    Ok(())
 }
 
-fn build_summary_prompt(message: &str, chunk_analyses: &[String]) -> String {
-    let combined_analyses = chunk_analyses
-        .iter()
-        .enumerate()
-        .map(|(i, analysis)| format!("**Chunk {}:**\n{}", i + 1, analysis))
-        .collect::<Vec<_>>()
-        .join("\n\n");
-
-    format!(
-        "Você é um engenheiro de software sênior consolidando análises de um commit grande.
+fn run_pr(settings: &Settings, base: &str, head: &str, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let analyzer = Analyzer::new(settings.clone())?;
+    let m = msg();
 
-CONTEXTO: Migração Java 8→17 e Hibernate 5→6
+    print_run_header(settings, &(m.analyzing_branch)(&head, &base));
 
-TAREFA: Com base nas análises dos chunks individuais, crie um resumo consolidado explicando:
-1. **PROPÓSITO**: Objetivo geral do commit
-2. **IMPACTO**: Efeito conjunto de todas as mudanças
-3. **OBSERVAÇÕES**: Pontos importantes da análise completa
-
---- MENSAGEM DO COMMIT ---
-{}
+    let Some(branch) = analyzer.analyze_branch(base, head)? else {
+        println!("{}[{}]{} {}", COLOR_YELLOW, m.label_skipped, COLOR_RESET, (m.no_branch_changes)(&head, &base));
+        return Ok(());
+    };
 
---- ANÁLISES DOS CHUNKS ---
-{}
+    let filename = output.unwrap_or_else(|| PathBuf::from(branch.filename()));
+    write_atomic(&filename, &branch.to_markdown())?;
+    println!("{}[{}]{} {}", COLOR_GREEN, m.label_success, COLOR_RESET, (m.analysis_saved)(&filename.display()));
 
---- RESUMO CONSOLIDADO ---",
-        message, combined_analyses
-    )
+    Ok(())
 }
 
-fn clean_ollama_response(response: String) -> String {
-    let mut cleaned = response;
-    
-    let think_patterns = [
-        "</think>",
-        "<think>",
-        "</thinking>",
-        "<thinking>",
-    ];
-    
-    for pattern in &think_patterns {
-        if let Some(pos) = cleaned.find(pattern) {
-            if pattern.starts_with("</") {
-                let content_start = pos + pattern.len();
-                cleaned = cleaned[content_start..].trim_start().to_string();
-            } else {
-                cleaned = cleaned[..pos].trim_end().to_string();
-            }
+fn run_message(settings: &Settings, conventional: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let analyzer = Analyzer::new(settings.clone())?;
+
+    match analyzer.commit_message(conventional)? {
+        Some(message) => {
+            println!("\n{}", SEPARATOR);
+            println!("{}", message);
+            println!("{}", SEPARATOR);
         }
+        None => println!("{}[{}]{} {}", COLOR_YELLOW, msg().label_skipped, COLOR_RESET, msg().no_staged_changes),
     }
-    
-    cleaned.trim().to_string()
+    Ok(())
 }
 
-fn generate_final_document(commit_info: &CommitInfo, analysis: &str, diff: &str) -> String {
-    let now = Local::now();
-    let formatted_date = now.format("%Y-%m-%d %H:%M:%S").to_string();
-    
-    format!(
-        "# Análise do Commit: {}
-
-## Informações do Commit
-
-**Hash Completo:** `{}`  
-**Hash Curto:** `{}`  
-**Autor:** {}  
-**Data do Commit:** {}  
-**Arquivos Modificados:** {}  
-**Linhas Adicionadas:** {}  
-**Linhas Removidas:** {}  
-
----
-
-## Análise Técnica
-
-{}
-
----
-
-## Detalhes das Alterações
-
-{}
+fn run_prepare_commit_msg(
+    settings: &Settings,
+    file: &Path,
+    source: Option<&str>,
+    conventional: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let current = std::fs::read_to_string(file).unwrap_or_default();
+    // nao mexe em mensagens do -m, merge, squash ou amend
+    if !hook::should_fill(source, &current) {
+        return Ok(());
+    }
 
----
+    let analyzer = Analyzer::new(settings.clone())?;
 
-*Relatório gerado em: {}*",
-        commit_info.message.lines().next().unwrap_or("Sem título"),
-        commit_info.hash,
-        commit_info.short_hash,
-        commit_info.author,
-        commit_info.date,
-        commit_info.files_changed.len(),
-        commit_info.insertions,
-        commit_info.deletions,
-        analysis,
-        diff,
-        formatted_date
-    )
+    if let Some(message) = analyzer.commit_message(conventional)? {
+        hook::write_message(file, &message)?;
+        println!("{}[{}]{} {}", COLOR_GREEN, msg().label_success, COLOR_RESET, (msg().message_written)(&file.display()));
+    }
+    Ok(())
 }
 
-fn generate_filename(message: &str) -> String {
-    let now = Local::now();
-    let date_prefix = now.format("%Y%m%d_%H%M%S").to_string();
-    
-    let safe_message = message
-        .lines()
-        .next()
-        .unwrap_or("commit")
-        .chars()
-        .take(40)
-        .map(|c| match c {
-            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
-            ' ' => '_',
-            _ => '_',
-        })
-        .collect::<String>()
-        .trim_matches('_')
-        .to_string();
-    
-    if safe_message.is_empty() {
-        format!("commit_{}_sem_titulo.md", date_prefix)
+fn run_install_hook(settings: &Settings, conventional: bool, uninstall: bool) -> Result<(), Box<dyn std::error::Error>> {
+    settings.validate()?;
+    let m = msg();
+
+    if uninstall {
+        let path = hook::uninstall(&settings.repo_path)?;
+        println!("{}[{}]{} {}", COLOR_GREEN, m.label_success, COLOR_RESET, (m.hook_removed)(&path.display()));
     } else {
-        format!("commit_{}_{}.md", date_prefix, safe_message)
+        let path = hook::install(&settings.repo_path, conventional)?;
+        println!("{}[{}]{} {}", COLOR_GREEN, m.label_success, COLOR_RESET, (m.hook_installed)(&hook::HOOK_NAME, &path.display()));
     }
+    Ok(())
 }
 
-fn call_ollama(client: &Client, prompt: String) -> Result<String, Box<dyn std::error::Error>> {
-    let ollama_req = OllamaRequest {
-        model: OLLAMA_MODEL,
-        prompt,
-        stream: false,
-    };
-
-    for attempt in 1..=MAX_RETRIES {
-        println!("{}[{}]{} Enviando requisição... (tentativa {}/{})", 
-                 COLOR_BLUE, LABEL_OLLAMA, COLOR_RESET, attempt, MAX_RETRIES);
-        
-        match client.post(OLLAMA_API_URL).json(&ollama_req).send() {
-            Ok(res) => {
-                if !res.status().is_success() {
-                    let error_msg = format!("Erro na API do Ollama: {}", res.status());
-                    if attempt < MAX_RETRIES {
-                        println!("{}[{}]{} {} - tentando novamente em {}ms...", 
-                                COLOR_YELLOW, LABEL_OLLAMA, COLOR_RESET, error_msg, RETRY_DELAY_MS);
-                        thread::sleep(Duration::from_millis(RETRY_DELAY_MS));
-                        continue;
-                    }
-                    return Err(error_msg.into());
-                }
-
-                match res.json::<OllamaResponse>() {
-                    Ok(ollama_res) => {
-                        if ollama_res.response.trim().is_empty() {
-                            let error_msg = "Resposta vazia do Ollama";
-                            if attempt < MAX_RETRIES {
-                                println!("{}[{}]{} {} - tentando novamente em {}ms...", 
-                                        COLOR_YELLOW, LABEL_OLLAMA, COLOR_RESET, error_msg, RETRY_DELAY_MS);
-                                thread::sleep(Duration::from_millis(RETRY_DELAY_MS));
-                                continue;
-                            }
-                            return Err(error_msg.into());
-                        }
-
-                        println!("{}[{}]{} Resposta recebida", COLOR_GREEN, LABEL_OLLAMA, COLOR_RESET);
-                        return Ok(ollama_res.response);
-                    }
-                    Err(e) => {
-                        let error_msg = format!("Erro ao decodificar resposta JSON: {}", e);
-                        if attempt < MAX_RETRIES {
-                            println!("{}[{}]{} {} - tentando novamente em {}ms...", 
-                                    COLOR_YELLOW, LABEL_OLLAMA, COLOR_RESET, error_msg, RETRY_DELAY_MS);
-                            thread::sleep(Duration::from_millis(RETRY_DELAY_MS));
-                            continue;
-                        }
-                        return Err(error_msg.into());
-                    }
-                }
-            }
-            Err(e) => {
-                let error_msg = format!("Erro de conexão: {}", e);
-                if attempt < MAX_RETRIES {
-                    println!("{}[{}]{} {} - tentando novamente em {}ms...", 
-                            COLOR_YELLOW, LABEL_OLLAMA, COLOR_RESET, error_msg, RETRY_DELAY_MS);
-                    thread::sleep(Duration::from_millis(RETRY_DELAY_MS));
-                    continue;
+// ate `jobs` commits ao mesmo tempo; cada handle devolve o resultado de um commit
+fn spawn_commits(
+    analyzer: Arc<Analyzer>,
+    cache: Arc<AnalysisCache>,
+    hashes: &[String],
+    jobs: usize,
+    force: bool,
+) -> Vec<JoinHandle<Result<Result<ProcessResult, String>, JoinError>>> {
+    let workers = Arc::new(Semaphore::new(jobs));
+    let total = hashes.len();
+
+    hashes
+        .iter()
+        .enumerate()
+        .map(|(index, hash)| {
+            let (analyzer, cache, workers, hash) = (analyzer.clone(), cache.clone(), workers.clone(), hash.clone());
+            tokio::spawn(async move {
+                let _permit = workers.acquire_owned().await.expect("semáforo nunca é fechado");
+                // depois do Ctrl-C os commits que ainda nao comecaram ficam de fora
+                if analyzer.cancel_token().is_cancelled() {
+                    return Ok(Ok(ProcessResult::NotStarted));
                 }
-                return Err(error_msg.into());
-            }
-        }
-    }
-
-    Err("Todas as tentativas falharam".into())
+                tokio::task::spawn_blocking(move || {
+                    let m = msg();
+                    println!("\n{}[{}]{} {}", COLOR_GREEN, m.label_processing, COLOR_RESET, (m.processing_commit)(&(index + 1), &total));
+                    process_commit(&analyzer, &cache, &hash, force).or_else(|e| {
+                        // interrompido no meio conta como nao processado, nao como erro
+                        if e.is::<Cancelled>() { Ok(ProcessResult::Cancelled) } else { Err(e.to_string()) }
+                    })
+                })
+                .await
+            })
+        })
+        .collect()
 }
 
-fn format_diff_as_markdown(diff_text: &str) -> String {
-    let mut formatted_output = String::new();
-    let mut in_diff_block = false;
-    let mut current_file = String::new();
-
-    let file_header_re = Regex::new(r"^diff --git a/(.*) b/").unwrap();
-    let hunk_header_re = Regex::new(r"^(@@ .* @@)(.*)").unwrap();
+enum CommitTarget {
+    Query(CommitQuery),
+    Single(String),
+}
 
-    let close_diff_block = |output: &mut String, in_block: &mut bool| {
-        if *in_block {
-            output.push_str("```\n\n");
-            *in_block = false;
-        }
-    };
+#[derive(Debug)]
+enum ProcessResult {
+    Success(String, CommitRisk),
+    Skipped(String),
+    Cached(String, CommitRisk),
+    NotStarted,
+    Cancelled,
+}
 
-    for line in diff_text.lines() {
-        if let Some(caps) = file_header_re.captures(line) {
-            close_diff_block(&mut formatted_output, &mut in_diff_block);
-            
-            let file_path = caps.get(1).map_or("", |m| m.as_str());
-            if file_path != current_file {
-                current_file = file_path.to_string();
-                writeln!(formatted_output, "### Arquivo: `{}`", file_path).unwrap();
-            }
-            
-        } else if let Some(caps) = hunk_header_re.captures(line) {
-            let _header = caps.get(1).map_or("", |m| m.as_str());
-            let _context = caps.get(2).map_or("", |m| m.as_str()).trim();
-            
-            close_diff_block(&mut formatted_output, &mut in_diff_block);
-            
-        } else if line.starts_with('+') || line.starts_with('-') || line.starts_with(' ') {
-            if !in_diff_block {
-                formatted_output.push_str("\n```diff\n");
-                in_diff_block = true;
-            }
-            writeln!(formatted_output, "{}", line).unwrap();
-        }
+fn process_commit(
+    analyzer: &Analyzer,
+    cache: &AnalysisCache,
+    hash: &str,
+    force: bool,
+) -> Result<ProcessResult, Box<dyn std::error::Error>> {
+    let key = CacheKey::new(hash, analyzer.settings());
+    if !force && let Some(entry) = cache.get(&key) {
+        let commit = git::get_commit_info(hash, &analyzer.settings().repo_path)?;
+        let risk = CommitRisk::assess(&commit, &entry.analysis, entry.structured.as_ref());
+        return Ok(ProcessResult::Cached(entry.report_file.display().to_string(), risk));
     }
 
-    close_diff_block(&mut formatted_output, &mut in_diff_block);
-    formatted_output
-}
+    let Some(report) = analyzer.analyze_commit(hash)? else {
+        return Ok(ProcessResult::Skipped(msg().no_code_changes.to_string()));
+    };
 
-fn get_commit_diff(hash: &str, repo_path: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let output = Command::new("git")
-        .arg("show")
-        .arg(hash)
-        .current_dir(repo_path)
-        .output()?;
+    let filename = report.filename();
+    write_atomic(Path::new(&filename), &report.to_markdown())?;
 
-    if !output.status.success() {
-        return Err(String::from_utf8(output.stderr)?.into());
+    // analise cortada ou com pedacos falhos fica fora do cache pra ser refeita
+    if report.complete {
+        cache.put(key, Path::new(&filename), &report)?;
     }
-    Ok(String::from_utf8(output.stdout)?)
+
+    Ok(ProcessResult::Success(filename, report.risk()))
 }
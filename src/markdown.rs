@@ -0,0 +1,69 @@
+use crate::diff::{FileDiff, Hunk, LineKind, parse_unified_diff};
+use crate::i18n::Messages;
+use std::fmt::Write as FmtWrite;
+
+pub fn format_diff_as_markdown(diff_text: &str, m: &Messages) -> String {
+    render_files(&parse_unified_diff(diff_text), m)
+}
+
+pub fn render_files(files: &[FileDiff], m: &Messages) -> String {
+    files.iter().map(|file| render_file(file, m)).collect()
+}
+
+// cabecalho do arquivo e um bloco ```diff por hunk
+pub fn render_file(file: &FileDiff, m: &Messages) -> String {
+    let mut output = render_file_header(file, m);
+    for hunk in &file.hunks {
+        output.push_str(&render_hunk(hunk));
+    }
+    if file.hunks.is_empty() {
+        output.push('\n');
+    }
+    output
+}
+
+// modo por arquivo: a analise do arquivo vem antes do diff dele
+pub fn render_file_with_note(file: &FileDiff, note: &str, m: &Messages) -> String {
+    let mut output = render_file_header(file, m);
+    write!(output, "\n{}\n", note.trim()).unwrap();
+    for hunk in &file.hunks {
+        output.push_str(&render_hunk(hunk));
+    }
+    output.push('\n');
+    output
+}
+
+// renomeacao, binario e troca de modo nao aparecem nas linhas; a descricao conta pro modelo
+pub fn render_file_header(file: &FileDiff, m: &Messages) -> String {
+    match file.describe(m) {
+        Some(description) => format!("### {}: `{}`\n*{}.*\n", m.file_heading, file.path(), description),
+        None => format!("### {}: `{}`\n", m.file_heading, file.path()),
+    }
+}
+
+pub fn render_hunk(hunk: &Hunk) -> String {
+    // a cerca precisa ser maior que qualquer sequencia de crases do codigo, senao o bloco fecha antes
+    let longest = hunk.lines.iter().map(|line| longest_backtick_run(&line.content)).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+
+    let mut output = format!("\n{}diff\n", fence);
+    // o cabecalho traz os numeros de linha e a funcao onde a mudanca esta
+    writeln!(output, "{}", hunk.header()).unwrap();
+    for line in &hunk.lines {
+        let prefix = match line.kind {
+            LineKind::Context => ' ',
+            LineKind::Added => '+',
+            LineKind::Removed => '-',
+        };
+        writeln!(output, "{}{}", prefix, line.content).unwrap();
+        if line.no_newline_at_eof {
+            output.push_str("\\ No newline at end of file\n");
+        }
+    }
+    writeln!(output, "{}\n", fence).unwrap();
+    output
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
@@ -0,0 +1,85 @@
+use crate::git::CommitInfo;
+use chrono::{DateTime, FixedOffset};
+use git2::{Commit, Diff, DiffFindOptions, DiffFormat, Oid, Repository};
+use std::path::Path;
+
+// leitura direta dos objetos com libgit2, sem abrir processos do git
+
+// como o git, aceita qualquer subdiretorio da arvore de trabalho
+fn open(repo_path: &Path) -> Result<Repository, git2::Error> {
+    Repository::discover(repo_path)
+}
+
+fn commit_diff<'r>(repo: &'r Repository, commit: &Commit) -> Result<Diff<'r>, Box<dyn std::error::Error>> {
+    // merges ficam com o `git show`, que mostra o diff combinado e nao o diff contra um pai
+    if commit.parent_count() > 1 {
+        return Err("commit de merge".into());
+    }
+
+    let parent_tree = match commit.parent_count() {
+        0 => None,
+        _ => Some(commit.parent(0)?.tree()?),
+    };
+    let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+
+    // mesma deteccao de renomeacao e copia do `git show -C`
+    diff.find_similar(Some(DiffFindOptions::new().renames(true).copies(true)))?;
+    Ok(diff)
+}
+
+fn commit_info(commit: &Commit, diff: &Diff) -> Result<CommitInfo, Box<dyn std::error::Error>> {
+    let author = commit.author();
+    let when = author.when();
+    let date = FixedOffset::east_opt(when.offset_minutes() * 60)
+        .zip(DateTime::from_timestamp(when.seconds(), 0))
+        .map(|(offset, date)| date.with_timezone(&offset).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+
+    let stats = diff.stats()?;
+    let files_changed = diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+
+    let hash = commit.id().to_string();
+    Ok(CommitInfo {
+        short_hash: hash[..12].to_string(),
+        hash,
+        message: commit.summary().unwrap_or("").to_string(),
+        body: commit.body().unwrap_or("").trim().to_string(),
+        author: author.name().unwrap_or("").to_string(),
+        date,
+        files_changed,
+        insertions: stats.insertions() as u32,
+        deletions: stats.deletions() as u32,
+    })
+}
+
+// patch no mesmo formato do `git show`, sem o cabecalho do commit
+fn patch_text(diff: &Diff) -> Result<String, Box<dyn std::error::Error>> {
+    let mut patch = Vec::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin() as u8);
+        }
+        patch.extend_from_slice(line.content());
+        true
+    })?;
+
+    Ok(String::from_utf8_lossy(&patch).into_owned())
+}
+
+pub fn get_commit_info(hash: &str, repo_path: &Path) -> Result<CommitInfo, Box<dyn std::error::Error>> {
+    let repo = open(repo_path)?;
+    let commit = repo.find_commit(Oid::from_str(hash)?)?;
+    commit_info(&commit, &commit_diff(&repo, &commit)?)
+}
+
+// informacoes e patch do mesmo diff, abrindo o repositorio uma vez so
+pub fn get_commit(hash: &str, repo_path: &Path) -> Result<(CommitInfo, String), Box<dyn std::error::Error>> {
+    let repo = open(repo_path)?;
+    let commit = repo.find_commit(Oid::from_str(hash)?)?;
+    let diff = commit_diff(&repo, &commit)?;
+    Ok((commit_info(&commit, &diff)?, patch_text(&diff)?))
+}
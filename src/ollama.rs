@@ -0,0 +1,504 @@
+use crate::config::{Settings, DEFAULT_NUM_CTX};
+use crate::i18n::Messages;
+use crate::llm::{ContextFuture, LlmBackend, LlmFuture};
+use crate::term::*;
+use reqwest::{Client, RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+// sem isso o Ollama usa a janela padrao do servidor, que pode ser menor que a do orcamento
+#[derive(Serialize, Clone, Copy)]
+struct OllamaOptions {
+    num_ctx: usize,
+}
+
+#[derive(Serialize)]
+struct OllamaRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    stream: bool,
+    options: OllamaOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'a Value>,
+}
+
+#[derive(Deserialize)]
+struct OllamaResponse {
+    response: String,
+}
+
+// formato de mensagem comum ao /api/chat e ao /v1/chat/completions
+#[derive(Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: &str) -> Self {
+        ChatMessage {
+            role: "user".to_string(),
+            content: content.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OllamaChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage>,
+    stream: bool,
+    options: OllamaOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'a Value>,
+}
+
+#[derive(Deserialize)]
+struct OllamaChatResponse {
+    message: ChatMessage,
+}
+
+// uma linha do NDJSON; /api/generate manda `response`, /api/chat manda `message`
+#[derive(Deserialize)]
+struct OllamaStreamChunk {
+    #[serde(default)]
+    response: String,
+    message: Option<ChatMessage>,
+    #[serde(default)]
+    done: bool,
+    error: Option<String>,
+}
+
+impl OllamaStreamChunk {
+    fn token(&self) -> &str {
+        match &self.message {
+            Some(message) => &message.content,
+            None => &self.response,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ShowRequest<'a> {
+    model: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ShowResponse {
+    // texto do Modelfile, ex: "num_ctx 8192\nstop <eos>"
+    #[serde(default)]
+    parameters: String,
+    // ex: "llama.context_length": 131072
+    #[serde(default)]
+    model_info: HashMap<String, Value>,
+}
+
+impl ShowResponse {
+    fn num_ctx(&self) -> Option<usize> {
+        self.parameters.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            (parts.next() == Some("num_ctx")).then(|| parts.next()?.parse().ok())?
+        })
+    }
+
+    fn context_length(&self) -> Option<usize> {
+        self.model_info
+            .iter()
+            .find(|(key, _)| key.ends_with(".context_length"))
+            .and_then(|(_, value)| value.as_u64())
+            .map(|value| value as usize)
+    }
+}
+
+/// Janela de contexto usada nas requisições: `num_ctx` da configuração, senão o do
+/// Modelfile, senão o máximo do modelo limitado a [`DEFAULT_NUM_CTX`]. Consulta o
+/// `/api/show` uma vez só.
+struct ModelContext {
+    show_url: String,
+    configured: Option<usize>,
+    timeout: Duration,
+    messages: &'static Messages,
+    resolved: OnceCell<usize>,
+}
+
+impl ModelContext {
+    fn new(settings: &Settings) -> Self {
+        ModelContext {
+            show_url: format!("{}/api/show", settings.ollama_url.trim_end_matches('/')),
+            configured: settings.num_ctx,
+            timeout: Duration::from_secs(settings.timeout_secs),
+            messages: settings.locale.messages(),
+            resolved: OnceCell::new(),
+        }
+    }
+
+    async fn get(&self, client: &Client, model: &str) -> usize {
+        *self.resolved.get_or_init(|| self.resolve(client, model)).await
+    }
+
+    async fn resolve(&self, client: &Client, model: &str) -> usize {
+        let m = self.messages;
+        let show = match self.show(client, model).await {
+            Ok(show) => Some(show),
+            Err(e) => {
+                println!("{}[{}]{} {}", COLOR_YELLOW, LABEL_OLLAMA, COLOR_RESET, (m.context_query_failed)(&e));
+                None
+            }
+        };
+        let max = show.as_ref().and_then(ShowResponse::context_length);
+
+        let mut num_ctx = self
+            .configured
+            .or_else(|| show.as_ref().and_then(ShowResponse::num_ctx))
+            .unwrap_or_else(|| max.map_or(DEFAULT_NUM_CTX, |max| max.min(DEFAULT_NUM_CTX)));
+        if let Some(max) = max
+            && num_ctx > max
+        {
+            println!("{}[{}]{} {}", COLOR_YELLOW, LABEL_OLLAMA, COLOR_RESET, (m.num_ctx_capped)(&num_ctx, &max));
+            num_ctx = max;
+        }
+
+        println!("{}[{}]{} {}", COLOR_BLUE, LABEL_OLLAMA, COLOR_RESET, (m.context_window)(&num_ctx));
+        num_ctx
+    }
+
+    async fn show(&self, client: &Client, model: &str) -> Result<ShowResponse, Box<dyn std::error::Error>> {
+        let request = client.post(&self.show_url).timeout(self.timeout).json(&ShowRequest { model });
+        let res = send(request, None, self.messages).await?;
+        Ok(res.json().await.map_err(|e| (self.messages.json_error)(&e))?)
+    }
+}
+
+// manda a requisicao; no streaming o tempo ate o primeiro byte tambem conta como tempo sem tokens
+async fn send(request: RequestBuilder, idle_timeout: Option<Duration>, m: &Messages) -> Result<Response, Box<dyn std::error::Error>> {
+    let res = match idle_timeout {
+        Some(idle) => tokio::time::timeout(idle, request.send())
+            .await
+            .map_err(|_| (m.idle_timeout)(&idle.as_secs()))?,
+        None => request.send().await,
+    }
+    .map_err(|e| (m.connection_error)(&e))?;
+
+    if !res.status().is_success() {
+        return Err((m.ollama_api_error)(&res.status()).into());
+    }
+    Ok(res)
+}
+
+// corpo da resposta em pedacos; nos testes vem da memoria
+trait ByteStream {
+    async fn next_bytes(&mut self) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>>;
+}
+
+impl ByteStream for Response {
+    async fn next_bytes(&mut self) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+        Ok(self.chunk().await?.map(|bytes| bytes.to_vec()))
+    }
+}
+
+// le o NDJSON mostrando os tokens conforme chegam, com limite de tempo entre um pedaco e outro
+async fn read_stream(mut res: impl ByteStream, idle_timeout: Duration, live: bool, m: &Messages) -> Result<String, Box<dyn std::error::Error>> {
+    let mut output = String::new();
+    let mut pending = Vec::new();
+    let mut done = false;
+    let mut failure = None;
+
+    // o terminal e travado so em cada escrita; segurar entre um await e outro pararia as
+    // outras threads que escrevem no stdout ate o fim da resposta
+    let print = |text: &str| {
+        let mut stdout = std::io::stdout().lock();
+        write!(stdout, "{}", text).ok();
+        stdout.flush().ok();
+    };
+    if live {
+        print(COLOR_GRAY);
+    }
+    'stream: loop {
+        let bytes = match tokio::time::timeout(idle_timeout, res.next_bytes()).await {
+            Ok(Ok(Some(bytes))) => bytes,
+            Ok(Ok(None)) => break,
+            Ok(Err(e)) => {
+                failure = Some((m.stream_interrupted)(&e));
+                break;
+            }
+            Err(_) => {
+                failure = Some((m.idle_timeout)(&idle_timeout.as_secs()));
+                break;
+            }
+        };
+        pending.extend_from_slice(&bytes);
+
+        while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let chunk: OllamaStreamChunk = match serde_json::from_str(&line) {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    failure = Some((m.json_error)(&e));
+                    break 'stream;
+                }
+            };
+            if let Some(error) = chunk.error {
+                failure = Some((m.ollama_api_error)(&error));
+                break 'stream;
+            }
+
+            if live {
+                print(chunk.token());
+            }
+            output.push_str(chunk.token());
+
+            if chunk.done {
+                done = true;
+                break 'stream;
+            }
+        }
+    }
+    if live {
+        print(&format!("{}\n", COLOR_RESET));
+    }
+
+    if done {
+        return Ok(output);
+    }
+    let reason = failure.unwrap_or_else(|| m.stream_ended.to_string());
+
+    // sem nenhum token nao ha o que aproveitar, deixa o call_llm tentar de novo
+    if output.trim().is_empty() {
+        return Err(reason.into());
+    }
+    println!("{}[{}]{} {}", COLOR_YELLOW, LABEL_OLLAMA, COLOR_RESET, (m.keeping_partial)(&reason, &output.chars().count()));
+    // a marca deixa a resposta fora do cache
+    Ok(format!("{}\n\n{}: {}]*", output.trim_end(), m.partial_marker, reason))
+}
+
+// tokens no terminal so com uma requisicao por vez no processo; com commits ou pedacos em
+// paralelo os cabecalhos e logs dos outros cairiam no meio da resposta
+fn live_output(settings: &Settings) -> bool {
+    settings.jobs == 1 && settings.max_requests_per_host == 1
+}
+
+// endpoint /api/generate
+pub struct OllamaGenerate {
+    client: Client,
+    url: String,
+    model: String,
+    stream: bool,
+    idle_timeout: Duration,
+    live: bool,
+    messages: &'static Messages,
+    context: ModelContext,
+}
+
+impl OllamaGenerate {
+    pub fn new(client: Client, settings: &Settings) -> Self {
+        OllamaGenerate {
+            client,
+            url: format!("{}/api/generate", settings.ollama_url.trim_end_matches('/')),
+            model: settings.model.clone(),
+            stream: settings.stream,
+            idle_timeout: Duration::from_secs(settings.idle_timeout_secs),
+            live: live_output(settings),
+            messages: settings.locale.messages(),
+            context: ModelContext::new(settings),
+        }
+    }
+}
+
+impl LlmBackend for OllamaGenerate {
+    fn label(&self) -> &'static str {
+        LABEL_OLLAMA
+    }
+
+    fn context_window(&self) -> ContextFuture<'_> {
+        Box::pin(self.context.get(&self.client, &self.model))
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str, format: Option<&'a Value>) -> LlmFuture<'a> {
+        Box::pin(async move {
+            let ollama_req = OllamaRequest {
+                model: &self.model,
+                prompt,
+                stream: self.stream,
+                options: OllamaOptions {
+                    num_ctx: self.context.get(&self.client, &self.model).await,
+                },
+                format,
+            };
+
+            let request = self.client.post(&self.url).json(&ollama_req);
+            let res = send(request, self.stream.then_some(self.idle_timeout), self.messages).await?;
+
+            if self.stream {
+                return read_stream(res, self.idle_timeout, self.live, self.messages).await;
+            }
+
+            let ollama_res: OllamaResponse = res
+                .json()
+                .await
+                .map_err(|e| (self.messages.json_error)(&e))?;
+            Ok(ollama_res.response)
+        })
+    }
+}
+
+// endpoint /api/chat
+pub struct OllamaChat {
+    client: Client,
+    url: String,
+    model: String,
+    stream: bool,
+    idle_timeout: Duration,
+    live: bool,
+    messages: &'static Messages,
+    context: ModelContext,
+}
+
+impl OllamaChat {
+    pub fn new(client: Client, settings: &Settings) -> Self {
+        OllamaChat {
+            client,
+            url: format!("{}/api/chat", settings.ollama_url.trim_end_matches('/')),
+            model: settings.model.clone(),
+            stream: settings.stream,
+            idle_timeout: Duration::from_secs(settings.idle_timeout_secs),
+            live: live_output(settings),
+            messages: settings.locale.messages(),
+            context: ModelContext::new(settings),
+        }
+    }
+}
+
+impl LlmBackend for OllamaChat {
+    fn label(&self) -> &'static str {
+        LABEL_OLLAMA
+    }
+
+    fn context_window(&self) -> ContextFuture<'_> {
+        Box::pin(self.context.get(&self.client, &self.model))
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str, format: Option<&'a Value>) -> LlmFuture<'a> {
+        Box::pin(async move {
+            let chat_req = OllamaChatRequest {
+                model: &self.model,
+                messages: vec![ChatMessage::user(prompt)],
+                stream: self.stream,
+                options: OllamaOptions {
+                    num_ctx: self.context.get(&self.client, &self.model).await,
+                },
+                format,
+            };
+
+            let request = self.client.post(&self.url).json(&chat_req);
+            let res = send(request, self.stream.then_some(self.idle_timeout), self.messages).await?;
+
+            if self.stream {
+                return read_stream(res, self.idle_timeout, self.live, self.messages).await;
+            }
+
+            let chat_res: OllamaChatResponse = res
+                .json()
+                .await
+                .map_err(|e| (self.messages.json_error)(&e))?;
+            Ok(chat_res.message.content)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Locale;
+    use std::collections::VecDeque;
+
+    // devolve os pedacos na ordem; no fim fecha o corpo ou, com `hang`, para de mandar sem fechar
+    struct Chunks {
+        chunks: VecDeque<&'static str>,
+        hang: bool,
+    }
+
+    impl Chunks {
+        fn new(chunks: &[&'static str]) -> Self {
+            Chunks { chunks: chunks.iter().copied().collect(), hang: false }
+        }
+    }
+
+    impl ByteStream for Chunks {
+        async fn next_bytes(&mut self) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+            match self.chunks.pop_front() {
+                Some(chunk) => Ok(Some(chunk.as_bytes().to_vec())),
+                None if self.hang => std::future::pending().await,
+                None => Ok(None),
+            }
+        }
+    }
+
+    async fn read(stream: Chunks) -> Result<String, Box<dyn std::error::Error>> {
+        read_stream(stream, Duration::from_millis(50), false, Locale::PtBr.messages()).await
+    }
+
+    #[tokio::test]
+    async fn complete_stream_joins_the_tokens() {
+        // linhas quebradas entre pedacos e o formato do /api/chat
+        let stream = Chunks::new(&[
+            "{\"response\":\"Olá\"}\n{\"resp",
+            "onse\":\", mundo\"}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"!\"},\"done\":true}\n",
+        ]);
+        assert_eq!(read(stream).await.unwrap(), "Olá, mundo!");
+    }
+
+    #[tokio::test]
+    async fn stream_cut_before_any_token_is_an_error() {
+        let stream = Chunks::new(&["{\"response\":\"Ol"]);
+        let error = read(stream).await.unwrap_err();
+        assert_eq!(error.to_string(), Locale::PtBr.messages().stream_ended);
+    }
+
+    #[tokio::test]
+    async fn truncated_stream_keeps_the_complete_lines() {
+        let stream = Chunks::new(&["{\"response\":\"Olá\"}\n{\"response\":\" mun"]);
+        let m = Locale::PtBr.messages();
+        assert_eq!(read(stream).await.unwrap(), format!("Olá\n\n{}: {}]*", m.partial_marker, m.stream_ended));
+    }
+
+    #[tokio::test]
+    async fn malformed_line_keeps_what_came_before() {
+        let stream = Chunks::new(&["{\"response\":\"Olá\"}\n", "isto não é json\n", "{\"response\":\" nunca\"}\n"]);
+        let response = read(stream).await.unwrap();
+
+        let m = Locale::PtBr.messages();
+        assert!(response.starts_with(&format!("Olá\n\n{}: Erro ao decodificar resposta JSON", m.partial_marker)));
+        assert!(!response.contains("nunca"));
+    }
+
+    #[tokio::test]
+    async fn eof_without_done_is_a_partial_answer() {
+        let stream = Chunks::new(&["{\"response\":\"Olá\",\"done\":false}\n"]);
+        let m = Locale::PtBr.messages();
+        assert_eq!(read(stream).await.unwrap(), format!("Olá\n\n{}: {}]*", m.partial_marker, m.stream_ended));
+    }
+
+    #[tokio::test]
+    async fn idle_timeout_is_a_partial_answer() {
+        let stream = Chunks { hang: true, ..Chunks::new(&["{\"response\":\"Olá\"}\n"]) };
+        let m = Locale::PtBr.messages();
+        assert_eq!(read(stream).await.unwrap(), format!("Olá\n\n{}: {}]*", m.partial_marker, (m.idle_timeout)(&0)));
+    }
+
+    #[tokio::test]
+    async fn server_error_line_stops_the_stream() {
+        let stream = Chunks::new(&["{\"error\":\"modelo não encontrado\"}\n"]);
+        let error = read(stream).await.unwrap_err();
+        assert_eq!(error.to_string(), (Locale::PtBr.messages().ollama_api_error)(&"modelo não encontrado"));
+    }
+}
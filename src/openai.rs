@@ -0,0 +1,101 @@
+use crate::config::{Settings, DEFAULT_NUM_CTX};
+use crate::i18n::Messages;
+use crate::llm::{ContextFuture, LlmBackend, LlmFuture};
+use crate::ollama::ChatMessage;
+use crate::term::*;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize)]
+struct ChatCompletionRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage>,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatCompletionChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionChoice {
+    message: ChatMessage,
+}
+
+// /v1/chat/completions: vLLM, LM Studio, llama.cpp server e afins
+pub struct OpenAiCompatible {
+    client: Client,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+    messages: &'static Messages,
+    // a API nao informa a janela do modelo
+    context_window: usize,
+}
+
+impl OpenAiCompatible {
+    pub fn new(client: Client, settings: &Settings) -> Self {
+        let base = settings.ollama_url.trim_end_matches('/');
+        // aceita a URL com ou sem o /v1 no final
+        let url = if base.ends_with("/v1") {
+            format!("{}/chat/completions", base)
+        } else {
+            format!("{}/v1/chat/completions", base)
+        };
+
+        OpenAiCompatible {
+            client,
+            url,
+            model: settings.model.clone(),
+            api_key: settings.api_key.clone(),
+            messages: settings.locale.messages(),
+            context_window: settings.num_ctx.unwrap_or(DEFAULT_NUM_CTX),
+        }
+    }
+}
+
+impl LlmBackend for OpenAiCompatible {
+    fn label(&self) -> &'static str {
+        LABEL_OPENAI
+    }
+
+    fn context_window(&self) -> ContextFuture<'_> {
+        let context_window = self.context_window;
+        Box::pin(async move { context_window })
+    }
+
+    // nem todo servidor compativel aceita response_format; o esquema so vai no prompt
+    fn complete<'a>(&'a self, prompt: &'a str, _format: Option<&'a Value>) -> LlmFuture<'a> {
+        Box::pin(async move {
+            let request = ChatCompletionRequest {
+                model: &self.model,
+                messages: vec![ChatMessage::user(prompt)],
+                stream: false,
+            };
+
+            let mut builder = self.client.post(&self.url).json(&request);
+            if let Some(key) = &self.api_key {
+                builder = builder.bearer_auth(key);
+            }
+
+            let res = builder.send().await.map_err(|e| (self.messages.connection_error)(&e))?;
+
+            if !res.status().is_success() {
+                return Err((self.messages.openai_api_error)(&res.status()).into());
+            }
+
+            let completion: ChatCompletionResponse = res
+                .json()
+                .await
+                .map_err(|e| (self.messages.json_error)(&e))?;
+            completion
+                .choices
+                .into_iter()
+                .next()
+                .map(|choice| choice.message.content)
+                .ok_or_else(|| self.messages.no_choices.into())
+        })
+    }
+}
@@ -0,0 +1,63 @@
+//! Perfis de análise: o papel pedido ao modelo, as seções esperadas na resposta e o
+//! formato do relatório.
+
+use crate::i18n::{msg, Messages, ProfileText};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Profile {
+    Migration,
+    Review,
+    Security,
+    Changelog,
+    Onboarding,
+}
+
+/// Seção pedida na resposta final: título e o que ela deve conter.
+pub type Section = (&'static str, &'static str);
+
+// os textos de cada perfil ficam nos catalogos de idioma
+impl Profile {
+    pub const ALL: [Profile; 5] = [Profile::Migration, Profile::Review, Profile::Security, Profile::Changelog, Profile::Onboarding];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Profile::Migration => "migration",
+            Profile::Review => "review",
+            Profile::Security => "security",
+            Profile::Changelog => "changelog",
+            Profile::Onboarding => "onboarding",
+        }
+    }
+
+    /// Textos do perfil no idioma do catálogo `m`.
+    pub fn text(&self, m: &'static Messages) -> &'static ProfileText {
+        let index = Profile::ALL.iter().position(|p| p == self).expect("perfil em Profile::ALL");
+        &m.profiles[index]
+    }
+
+    /// O changelog é para quem não lê o código; os outros relatórios trazem o diff.
+    pub fn includes_diff(&self) -> bool {
+        !matches!(self, Profile::Changelog)
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Profile::ALL.into_iter().find(|profile| profile.name() == s).ok_or_else(|| {
+            let names: Vec<&str> = Profile::ALL.iter().map(|p| p.name()).collect();
+            (msg().unknown_profile)(&s, &names.join(", "))
+        })
+    }
+}
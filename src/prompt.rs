@@ -0,0 +1,263 @@
+use crate::analysis::{RiskLevel, FIELDS};
+use crate::analyzer::AnalysisKind;
+use crate::config::Settings;
+use crate::git::CommitInfo;
+use crate::i18n::{Messages, ProfileText};
+use crate::profile::Profile;
+use crate::template::Template;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// aceita a secao em qualquer idioma, o modelo nem sempre responde no pedido
+pub fn extract_risk_level(analysis: &str) -> Option<RiskLevel> {
+    static RISK_RE: OnceLock<Regex> = OnceLock::new();
+    let risk_re = RISK_RE.get_or_init(|| Regex::new(r"(?i)(?:RISCO|RISK)\W*(BAIXO|M[ÉE]DIO|ALTO|LOW|MEDIUM|HIGH)").unwrap());
+    let caps = risk_re.captures(analysis)?;
+    match caps.get(1)?.as_str().to_uppercase().as_str() {
+        "ALTO" | "HIGH" => Some(RiskLevel::High),
+        "BAIXO" | "LOW" => Some(RiskLevel::Low),
+        _ => Some(RiskLevel::Medium),
+    }
+}
+
+/// Templates que podem ser substituídos, na ordem em que o `render` os lista.
+pub const TEMPLATE_NAMES: [&str; 7] = [
+    "analysis",
+    "chunk",
+    "summary",
+    "file_analysis",
+    "file_synthesis",
+    "group_summary",
+    "commit_message",
+];
+
+/// Variáveis aceitas nos templates; as que não se aplicam a um prompt ficam vazias.
+pub const TEMPLATE_VARIABLES: [&str; 16] = [
+    "role",
+    "task",
+    "sections",
+    "focus",
+    "message",
+    "body",
+    "stats",
+    "files",
+    "diff",
+    "analyses",
+    "chunk_index",
+    "chunk_total",
+    "project_context",
+    "pending",
+    "conventional",
+    "language",
+];
+
+const TEMPLATE_EXTENSION: &str = "txt";
+
+/// Templates de prompt carregados: os embutidos no idioma das configurações, com os arquivos
+/// `<nome>.txt` do diretório `templates` no lugar dos correspondentes.
+#[derive(Debug, Clone)]
+pub struct PromptTemplates {
+    messages: &'static Messages,
+    templates: Vec<Template>,
+    profile: Profile,
+    project_context: String,
+    structured: bool,
+    fingerprint: String,
+}
+
+impl PromptTemplates {
+    pub fn load(settings: &Settings) -> Result<Self, Box<dyn std::error::Error>> {
+        let m = settings.locale.messages();
+        let mut sources: Vec<String> = m.templates.iter().map(|s| s.to_string()).collect();
+
+        if let Some(dir) = &settings.templates {
+            let entries = std::fs::read_dir(dir)
+                .map_err(|e| (m.templates_dir_unreadable)(&dir.display(), &e))?;
+            for entry in entries {
+                let path = entry?.path();
+                if path.extension().and_then(|e| e.to_str()) != Some(TEMPLATE_EXTENSION) {
+                    continue;
+                }
+                let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+                let Some(i) = TEMPLATE_NAMES.iter().position(|n| *n == name) else {
+                    return Err((m.unknown_template)(&path.display(), &TEMPLATE_NAMES.join(", ")).into());
+                };
+                sources[i] = std::fs::read_to_string(&path)
+                    .map_err(|e| (m.read_failed)(&path.display(), &e))?;
+            }
+        }
+
+        let mut hasher = Sha256::new();
+        let mut templates = Vec::new();
+        for (name, source) in TEMPLATE_NAMES.iter().zip(&sources) {
+            // o arquivo termina com quebra de linha, o prompt termina no marcador da resposta
+            let source = source.trim_end();
+            hasher.update(name);
+            hasher.update(source);
+            templates.push(Template::parse(source, &TEMPLATE_VARIABLES, m).map_err(|e| (m.template_error)(name, &e))?);
+        }
+        // o texto do perfil entra nos prompts tanto quanto o dos templates
+        let profile = settings.profile;
+        let profile_text = profile.text(m);
+        let project_context = settings.project_context.clone().unwrap_or_else(|| profile_text.default_context.to_string());
+        for text in [profile.name(), profile_text.role, profile_text.task, &project_context, m.language] {
+            hasher.update(text);
+        }
+        if settings.structured {
+            hasher.update(m.json_instructions);
+            for description in m.json_fields {
+                hasher.update(description);
+            }
+        }
+        for (title, description) in profile_text.sections {
+            hasher.update(title);
+            hasher.update(description);
+        }
+        for item in profile_text.focus {
+            hasher.update(item);
+        }
+
+        Ok(PromptTemplates {
+            messages: m,
+            templates,
+            profile,
+            project_context,
+            structured: settings.structured,
+            fingerprint: format!("{:x}", hasher.finalize()),
+        })
+    }
+
+    /// Muda sempre que o texto de algum template, o perfil, o contexto do projeto, o idioma ou o modo estruturado muda;
+    /// entra na chave do cache.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    fn render(&self, name: &str, vars: HashMap<&str, String>) -> String {
+        let i = TEMPLATE_NAMES.iter().position(|n| *n == name).expect("template conhecido");
+        self.templates[i].render(&vars)
+    }
+
+    // o que vale para todos os prompts de um commit; `structured` troca as secoes pelos campos do JSON
+    fn commit_vars(&self, commit: &CommitInfo, kind: AnalysisKind, structured: bool) -> HashMap<&'static str, String> {
+        let m = self.messages;
+        let text = self.profile.text(m);
+        HashMap::from([
+            ("role", text.role.to_string()),
+            ("task", text.task.to_string()),
+            ("sections", if structured { render_json_fields(text, m) } else { render_sections(text, m) }),
+            ("focus", text.focus.iter().map(|item| format!("- {}", item)).collect::<Vec<_>>().join("\n")),
+            ("message", commit.message.clone()),
+            ("body", commit.body.clone()),
+            ("stats", (m.prompt_stats)(&commit.files_changed.len(), &commit.insertions, &commit.deletions)),
+            ("files", commit.files_changed.join("\n")),
+            ("project_context", self.project_context.clone()),
+            ("pending", if kind == AnalysisKind::Pending { m.yes.to_string() } else { String::new() }),
+            ("language", m.language.to_string()),
+        ])
+    }
+
+    pub fn build_analysis_prompt(&self, kind: AnalysisKind, commit: &CommitInfo, diff: &str) -> String {
+        let mut vars = self.commit_vars(commit, kind, self.structured);
+        vars.insert("diff", diff.to_string());
+        self.render("analysis", vars)
+    }
+
+    pub fn build_chunk_analysis_prompt(&self, commit: &CommitInfo, chunk: &str, chunk_num: usize, total_chunks: usize) -> String {
+        let mut vars = self.commit_vars(commit, AnalysisKind::Commit, false);
+        vars.insert("diff", chunk.to_string());
+        vars.insert("chunk_index", chunk_num.to_string());
+        vars.insert("chunk_total", total_chunks.to_string());
+        self.render("chunk", vars)
+    }
+
+    /// `structured` só vale quando o resumo é a resposta final do relatório.
+    pub fn build_summary_prompt(&self, kind: AnalysisKind, commit: &CommitInfo, chunk_analyses: &[String], structured: bool) -> String {
+        let combined_analyses = chunk_analyses
+            .iter()
+            .enumerate()
+            .map(|(i, analysis)| format!("{}\n{}", (self.messages.prompt_chunk_item)(&(i + 1)), analysis))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let mut vars = self.commit_vars(commit, kind, structured);
+        vars.insert("analyses", combined_analyses);
+        self.render("summary", vars)
+    }
+
+    // modo por arquivo: um arquivo (ou alguns pequenos da mesma pasta) por vez
+    pub fn build_file_analysis_prompt(&self, commit: &CommitInfo, files: &[String], diff: &str) -> String {
+        let mut vars = self.commit_vars(commit, AnalysisKind::Commit, false);
+        vars.insert("files", files.join("\n"));
+        vars.insert("diff", diff.to_string());
+        self.render("file_analysis", vars)
+    }
+
+    pub fn build_file_synthesis_prompt(&self, kind: AnalysisKind, commit: &CommitInfo, file_analyses: &[String], structured: bool) -> String {
+        let mut vars = self.commit_vars(commit, kind, structured);
+        vars.insert("analyses", file_analyses.join("\n\n"));
+        self.render("file_synthesis", vars)
+    }
+
+    // nivel intermediario da reducao: resume um grupo de analises sem fechar a conclusao
+    pub fn build_group_summary_prompt(&self, commit: &CommitInfo, analyses: &[String], group_num: usize, total_groups: usize) -> String {
+        let combined_analyses = analyses
+            .iter()
+            .enumerate()
+            .map(|(i, analysis)| format!("{}\n{}", (self.messages.prompt_part_item)(&(i + 1)), analysis))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let mut vars = self.commit_vars(commit, AnalysisKind::Commit, false);
+        vars.insert("analyses", combined_analyses);
+        vars.insert("chunk_index", group_num.to_string());
+        vars.insert("chunk_total", total_groups.to_string());
+        self.render("group_summary", vars)
+    }
+
+    pub fn build_commit_message_prompt(&self, commit: &CommitInfo, changes: &str, conventional: bool) -> String {
+        let mut vars = self.commit_vars(commit, AnalysisKind::Pending, false);
+        vars.insert("diff", changes.to_string());
+        vars.insert("conventional", if conventional { self.messages.yes.to_string() } else { String::new() });
+        self.render("commit_message", vars)
+    }
+}
+
+// lista numerada das secoes da resposta final; o nivel de risco vai sempre, a pontuacao usa
+fn render_sections(text: &ProfileText, m: &Messages) -> String {
+    text.sections
+        .iter()
+        .chain([&m.risk_section])
+        .enumerate()
+        .map(|(i, (title, description))| format!("{}. **{}**: {}", i + 1, title, description))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// no modo estruturado as secoes do perfil viram o que o campo `observations` deve cobrir;
+// o risco e sempre pedido
+fn render_json_fields(text: &ProfileText, m: &Messages) -> String {
+    let sections: Vec<&str> = text.sections.iter().map(|(title, _)| *title).collect();
+    let fields = FIELDS.iter().zip(m.json_fields).map(|(name, description)| match *name {
+        "observations" => format!("- \"{}\": {}, {}", name, description, (m.json_covering)(&sections.join(", "))),
+        _ => format!("- \"{}\": {}", name, description),
+    });
+    std::iter::once(m.json_instructions.to_string()).chain(fields).collect::<Vec<_>>().join("\n")
+}
+
+// tira cercas de codigo e aspas que alguns modelos insistem em colocar
+pub fn clean_commit_message(response: &str) -> String {
+    let lines: Vec<&str> = response
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .collect();
+
+    lines
+        .join("\n")
+        .trim()
+        .trim_matches(|c| c == '"' || c == '`')
+        .trim()
+        .to_string()
+}
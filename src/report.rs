@@ -0,0 +1,282 @@
+use crate::analysis::Analysis;
+use crate::analyzer::{AnalysisReport, Reduction};
+use crate::diff::FileChange;
+use crate::filter::ExcludedFile;
+use crate::git::{self, CommitInfo};
+use crate::i18n::Messages;
+use crate::risk::{CommitRisk, MAX_SCORE};
+use chrono::prelude::*;
+use std::fmt::Write as FmtWrite;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub fn generate_final_document(report: &AnalysisReport) -> String {
+    let m = report.locale.messages();
+    let commit_info = &report.commit;
+    let profile = report.profile;
+    let now = Local::now();
+    let formatted_date = now.format("%Y-%m-%d %H:%M:%S").to_string();
+    
+    format!(
+        "# {}: {}
+
+## {}
+
+**{}:** `{}`  
+**{}:** `{}`  
+**{}:** {}  
+**{}:** {}  
+**{}:** {}  
+**{}:** {}  
+**{}:** {}  
+{}{}
+{}---
+
+## {}
+
+{}
+
+---
+
+{}*{}: {}*",
+        profile.text(m).commit_title,
+        commit_info.message.lines().next().unwrap_or(m.untitled),
+        m.commit_info,
+        m.full_hash, commit_info.hash,
+        m.short_hash, commit_info.short_hash,
+        m.author, commit_info.author,
+        m.commit_date, commit_info.date,
+        m.files_modified, commit_info.files_changed.len(),
+        m.lines_added, commit_info.insertions,
+        m.lines_removed, commit_info.deletions,
+        risk_line(&report.risk(), m),
+        reduction_line(report.reduction, m),
+        files_table(&report.files, m),
+        profile.text(m).analysis_heading,
+        report.analysis,
+        details_section(report, m),
+        m.generated_at,
+        formatted_date
+    )
+}
+
+pub fn generate_pr_document(
+    base: &str,
+    head: &str,
+    merge_base: &str,
+    commits: &[CommitInfo],
+    report: &AnalysisReport,
+) -> String {
+    let m = report.locale.messages();
+    let range_info = &report.commit;
+    let now = Local::now();
+    let formatted_date = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let mut appendix = table_header(&[m.col_hash, m.col_date, m.col_author, m.col_message, m.col_changes]);
+    for commit in commits {
+        writeln!(
+            appendix,
+            "| `{}` | {} | {} | {} | {} |",
+            commit.short_hash,
+            commit.date,
+            commit.author.replace('|', "\\|"),
+            commit.message.replace('|', "\\|"),
+            (m.appendix_changes)(&commit.insertions, &commit.deletions, &commit.files_changed.len())
+        )
+        .unwrap();
+    }
+
+    format!(
+        "# {}: {} → {}
+
+## {}
+
+**Base:** `{}`  
+**Head:** `{}` (`{}`)  
+**Merge-base:** `{}`  
+**Commits:** {}  
+**{}:** {}  
+**{}:** {}  
+**{}:** {}  
+{}{}
+{}---
+
+## {}
+
+{}
+
+---
+
+{}## {}
+
+{}
+---
+
+*{}: {}*",
+        report.profile.text(m).branch_title,
+        head,
+        base,
+        m.branch_info,
+        base,
+        head,
+        range_info.short_hash,
+        git::short_hash(merge_base),
+        commits.len(),
+        m.files_modified, range_info.files_changed.len(),
+        m.lines_added, range_info.insertions,
+        m.lines_removed, range_info.deletions,
+        risk_line(&report.risk(), m),
+        reduction_line(report.reduction, m),
+        files_table(&report.files, m),
+        report.profile.text(m).analysis_heading,
+        report.analysis,
+        details_section(report, m),
+        m.appendix,
+        appendix,
+        m.generated_at,
+        formatted_date
+    )
+}
+
+/// Texto da análise estruturada no relatório: os campos de texto como subseções e o resto
+/// numa lista no fim.
+pub fn render_analysis(analysis: &Analysis, m: &Messages) -> String {
+    let mut text = String::new();
+    for (heading, body) in [
+        (m.field_purpose, &analysis.purpose),
+        (m.field_impact, &analysis.impact),
+        (m.field_observations, &analysis.observations),
+    ] {
+        if !body.trim().is_empty() {
+            write!(text, "### {}\n\n{}\n\n", heading, body.trim()).unwrap();
+        }
+    }
+
+    let or_dash = |items: Vec<String>| if items.is_empty() { "—".to_string() } else { items.join(", ") };
+    let categories = or_dash(analysis.categories.iter().map(|c| c.name().to_string()).collect());
+    let components = or_dash(analysis.components.iter().map(|c| format!("`{}`", c)).collect());
+    write!(
+        text,
+        "**{}:** {}  \n**{}:** {}  \n**{}:** {}  \n**{}:** {}",
+        m.field_risk, analysis.risk_level.label(m),
+        m.field_categories, categories,
+        m.field_components, components,
+        m.field_breaking_change, if analysis.breaking_change { m.yes } else { m.no }
+    )
+    .unwrap();
+    text
+}
+
+// diff e arquivos fora da analise; o changelog nao traz o diff, a tabela de arquivos ja diz o que mudou
+fn details_section(report: &AnalysisReport, m: &Messages) -> String {
+    let mut section = String::new();
+    if report.profile.includes_diff() {
+        write!(section, "## {}\n\n{}", m.details, report.formatted_diff).unwrap();
+    }
+    section.push_str(&excluded_section(&report.excluded, m));
+    if !section.is_empty() {
+        section.push_str("\n\n---\n\n");
+    }
+    section
+}
+
+// linha de titulos e a de separacao, com os nomes das colunas no idioma do relatório
+fn table_header(columns: &[&str]) -> String {
+    let rules: Vec<String> = columns.iter().map(|c| "-".repeat(c.chars().count() + 2)).collect();
+    format!("| {} |\n|{}|\n", columns.join(" | "), rules.join("|"))
+}
+
+// renomeacoes, binarios e trocas de modo nao tem linhas; a coluna do tipo mostra o que houve
+fn files_table(files: &[FileChange], m: &Messages) -> String {
+    if files.is_empty() {
+        return String::new();
+    }
+
+    let mut table = format!("### {}\n\n{}", m.files, table_header(&[m.col_file, m.col_kind, m.col_changes]));
+    for file in files {
+        let changes = if file.binary { "—".to_string() } else { format!("+{} -{}", file.insertions, file.deletions) };
+        writeln!(table, "| `{}` | {} | {} |", file.path.replace('|', "\\|"), file.description.replace('|', "\\|"), changes).unwrap();
+    }
+    table.push('\n');
+    table
+}
+
+// arquivos que os filtros tiraram do prompt continuam no relatorio, com as estatisticas
+fn excluded_section(excluded: &[ExcludedFile], m: &Messages) -> String {
+    if excluded.is_empty() {
+        return String::new();
+    }
+
+    let mut section = format!("### {}\n\n{}", m.excluded_files, table_header(&[m.col_file, m.col_changes, m.col_reason]));
+    for file in excluded {
+        let reason = match &file.pattern {
+            Some(pattern) => format!("`{}`", pattern),
+            None => m.outside_include.to_string(),
+        };
+        writeln!(section, "| `{}` | +{} -{} | {} |", file.path.replace('|', "\\|"), file.insertions, file.deletions, reason).unwrap();
+    }
+    section
+}
+
+fn risk_line(risk: &CommitRisk, m: &Messages) -> String {
+    let categories = if risk.categories.is_empty() { String::new() } else { format!(" ({})", risk.category_names()) };
+    format!("**{}:** {}/{}{}  \n", m.risk_score, risk.score, MAX_SCORE, categories)
+}
+
+// so aparece quando o diff foi dividido
+fn reduction_line(reduction: Option<Reduction>, m: &Messages) -> String {
+    match reduction {
+        Some(reduction) => format!(
+            "**{}:** {} ({})  \n",
+            if reduction.per_file { m.per_file_analyses } else { m.chunks_analyzed },
+            reduction.chunks,
+            (m.reduction_depth)(&reduction.depth)
+        ),
+        None => String::new(),
+    }
+}
+
+// grava num temporario e renomeia, pra nunca deixar um arquivo pela metade; o temporario
+// e unico por escrita, entao escritas paralelas no mesmo destino nao se misturam
+pub fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let unique = NEXT.fetch_add(1, Ordering::Relaxed);
+    let tmp = path.with_file_name(format!(".{}.{}.{}.tmp", name, std::process::id(), unique));
+
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+// o hash separa commits com o mesmo assunto terminados no mesmo segundo
+pub fn generate_filename(short_hash: &str, message: &str, m: &Messages) -> String {
+    let now = Local::now();
+    let date_prefix = now.format("%Y%m%d_%H%M%S").to_string();
+    
+    let safe_message = safe_file_part(message.lines().next().unwrap_or("commit"));
+    let safe_message = if safe_message.is_empty() { m.untitled_file.to_string() } else { safe_message };
+
+    format!("commit_{}_{}_{}.md", date_prefix, safe_file_part(short_hash), safe_message)
+}
+
+pub fn generate_pr_filename(base: &str, head: &str) -> String {
+    let now = Local::now();
+    let date_prefix = now.format("%Y%m%d_%H%M%S").to_string();
+
+    format!("pr_{}_{}_{}.md", date_prefix, safe_file_part(head), safe_file_part(base))
+}
+
+fn safe_file_part(text: &str) -> String {
+    text.chars()
+        .take(40)
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+            ' ' => '_',
+            _ => '_',
+        })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string()
+}
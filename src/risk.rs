@@ -0,0 +1,385 @@
+//! Pontuação de risco e classificação de cada commit analisado, para ordenar um intervalo.
+//!
+//! A pontuação (0 a 100) soma o tamanho do diff, quantos arquivos ele toca, o tipo dos
+//! arquivos (schema, dependências, configuração) e a avaliação do modelo; commits que só
+//! mexem em testes pesam um terço.
+
+use crate::analysis::{Analysis, Category, RiskLevel};
+use crate::git::CommitInfo;
+use crate::prompt::extract_risk_level;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::Regex;
+use std::sync::OnceLock;
+
+pub const MAX_SCORE: u32 = 100;
+
+const TEST_PATTERNS: &[&str] = &[
+    "**/test/**",
+    "**/tests/**",
+    "**/__tests__/**",
+    "**/spec/**",
+    "**/*_test.*",
+    "**/*.test.*",
+    "**/*.spec.*",
+    "**/test_*.py",
+    "**/*Test.java",
+    "**/*Tests.java",
+    "**/*Test.kt",
+    "**/__snapshots__/**",
+    "**/*.snap",
+];
+
+const DEPENDENCY_PATTERNS: &[&str] = &[
+    "**/Cargo.toml",
+    "**/Cargo.lock",
+    "**/package.json",
+    "**/package-lock.json",
+    "**/npm-shrinkwrap.json",
+    "**/yarn.lock",
+    "**/pnpm-lock.yaml",
+    "**/pom.xml",
+    "**/build.gradle",
+    "**/build.gradle.kts",
+    "**/gradle/libs.versions.toml",
+    "**/go.mod",
+    "**/go.sum",
+    "**/requirements*.txt",
+    "**/pyproject.toml",
+    "**/poetry.lock",
+    "**/Pipfile",
+    "**/Pipfile.lock",
+    "**/Gemfile",
+    "**/Gemfile.lock",
+    "**/composer.json",
+    "**/composer.lock",
+    "**/vendor/**",
+    "**/third_party/**",
+];
+
+const SCHEMA_PATTERNS: &[&str] = &[
+    "**/*.sql",
+    "**/*.proto",
+    "**/*.graphql",
+    "**/*.gql",
+    "**/*.avsc",
+    "**/*.prisma",
+    "**/migrations/**",
+    "**/migration/**",
+    "**/db/migrate/**",
+    "**/*.xsd",
+    "**/*schema*.json",
+];
+
+const CONFIG_PATTERNS: &[&str] = &[
+    "**/*.yml",
+    "**/*.yaml",
+    "**/*.toml",
+    "**/*.ini",
+    "**/*.cfg",
+    "**/*.conf",
+    "**/*.properties",
+    "**/.env*",
+    "**/Dockerfile*",
+    "**/docker-compose*",
+    "**/.github/**",
+    "**/.gitlab-ci.yml",
+    "**/*.config.js",
+    "**/*.config.ts",
+];
+
+// o que cada arquivo e; a primeira lista que bate vence
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FileKind {
+    Test,
+    Dependency,
+    Schema,
+    Config,
+    Code,
+}
+
+fn file_kind(path: &str) -> FileKind {
+    static SETS: OnceLock<[(FileKind, GlobSet); 4]> = OnceLock::new();
+    let sets = SETS.get_or_init(|| {
+        [
+            (FileKind::Test, glob_set(TEST_PATTERNS)),
+            (FileKind::Dependency, glob_set(DEPENDENCY_PATTERNS)),
+            (FileKind::Schema, glob_set(SCHEMA_PATTERNS)),
+            (FileKind::Config, glob_set(CONFIG_PATTERNS)),
+        ]
+    });
+    sets.iter()
+        .find(|(_, set)| set.is_match(path))
+        .map_or(FileKind::Code, |(kind, _)| *kind)
+}
+
+fn glob_set(patterns: &[&str]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).expect("padrão embutido válido"));
+    }
+    builder.build().expect("padrões embutidos válidos")
+}
+
+// prefixo do conventional commits, senao palavras-chave em portugues ou ingles
+fn message_categories(message: &str) -> Vec<Category> {
+    static PREFIX: OnceLock<Regex> = OnceLock::new();
+    static KEYWORDS: OnceLock<[(Category, Regex); 3]> = OnceLock::new();
+
+    let prefix = PREFIX.get_or_init(|| Regex::new(r"(?i)^(feat|fix|bugfix|hotfix|refactor|perf|test|build|deps|chore\(deps\))(\([^)]*\))?!?:").unwrap());
+    if let Some(caps) = prefix.captures(message) {
+        let category = match caps[1].to_lowercase().as_str() {
+            "feat" => Category::Feature,
+            "fix" | "bugfix" | "hotfix" => Category::Bugfix,
+            "refactor" | "perf" => Category::Refactor,
+            "test" => Category::TestOnly,
+            _ => Category::Dependency,
+        };
+        return vec![category];
+    }
+
+    let keywords = KEYWORDS.get_or_init(|| {
+        [
+            (Category::Bugfix, Regex::new(r"(?i)\b(fix(es|ed)?|bug(s|fix)?|corrige|correção|conserta|hotfix)\b").unwrap()),
+            (Category::Feature, Regex::new(r"(?i)\b(add(s|ed)?|implement(s|ed)?|adiciona|implementa|nova|novo|suporte)\b").unwrap()),
+            (Category::Refactor, Regex::new(r"(?i)\b(refactor(s|ed|ing)?|refatora(ção|do)?|renam(e|es|ed)|renomeia|extrai|extract(s|ed)?|cleanup|limpa|simplifica)\b").unwrap()),
+        ]
+    });
+    keywords
+        .iter()
+        .filter(|(_, re)| re.is_match(message))
+        .map(|(category, _)| *category)
+        .collect()
+}
+
+/// Risco e classificação de um commit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitRisk {
+    pub short_hash: String,
+    /// Primeira linha da mensagem.
+    pub message: String,
+    pub score: u32,
+    /// Nível dado pelo modelo, quando ele avaliou.
+    pub level: Option<RiskLevel>,
+    /// Em [`Category::ALL`]; junta as do modelo com as das heurísticas.
+    pub categories: Vec<Category>,
+    pub breaking_change: bool,
+}
+
+impl CommitRisk {
+    /// Usa a análise estruturada quando houver; senão procura o nível de risco no texto.
+    pub fn assess(commit: &CommitInfo, analysis: &str, structured: Option<&Analysis>) -> Self {
+        let kinds: Vec<FileKind> = commit.files_changed.iter().map(|path| file_kind(path)).collect();
+        let has = |kind: FileKind| kinds.contains(&kind);
+        let test_only = !kinds.is_empty() && kinds.iter().all(|k| *k == FileKind::Test);
+
+        let mut categories = message_categories(&commit.message);
+        if let Some(structured) = structured {
+            categories.extend(&structured.categories);
+        }
+        for (kind, category) in [
+            (FileKind::Dependency, Category::Dependency),
+            (FileKind::Schema, Category::Schema),
+            (FileKind::Config, Category::Config),
+        ] {
+            if has(kind) {
+                categories.push(category);
+            }
+        }
+        // so testes e decidido pelos arquivos, nao pela mensagem nem pelo modelo, e exclui as outras
+        if test_only {
+            categories = vec![Category::TestOnly];
+        } else {
+            categories.retain(|c| *c != Category::TestOnly);
+        }
+        let categories: Vec<Category> = Category::ALL.into_iter().filter(|c| categories.contains(c)).collect();
+
+        let level = structured.map(|s| s.risk_level).or_else(|| extract_risk_level(analysis));
+        let breaking_change = structured.is_some_and(|s| s.breaking_change);
+
+        // ate 30 pontos pelo tamanho, crescendo devagar: ~20 com 100 linhas, 30 com 1000
+        let lines = (commit.insertions + commit.deletions) as f64;
+        let size = ((lines + 1.0).log2() * 3.0).min(30.0) as u32;
+        let spread = (commit.files_changed.len() as u32 * 2).min(15);
+        let sensitive = [(FileKind::Schema, 15), (FileKind::Dependency, 10), (FileKind::Config, 5)]
+            .iter()
+            .filter(|(kind, _)| has(*kind))
+            .map(|(_, points)| points)
+            .sum::<u32>();
+        let model = match level {
+            Some(RiskLevel::High) => 30,
+            Some(RiskLevel::Medium) => 15,
+            Some(RiskLevel::Low) | None => 0,
+        };
+        let breaking = if breaking_change { 15 } else { 0 };
+
+        let mut score = size + spread + sensitive + model + breaking;
+        if test_only {
+            score /= 3;
+        }
+
+        CommitRisk {
+            short_hash: commit.short_hash.clone(),
+            message: commit.message.lines().next().unwrap_or("").to_string(),
+            score: score.min(MAX_SCORE),
+            level,
+            categories,
+            breaking_change,
+        }
+    }
+
+    pub fn category_names(&self) -> String {
+        self.categories.iter().map(Category::name).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Do maior risco para o menor; empates mantêm a ordem recebida.
+pub fn rank(risks: &mut [CommitRisk]) {
+    risks.sort_by_key(|risk| std::cmp::Reverse(risk.score));
+}
+
+/// Quantos commits caíram em cada categoria, na ordem de [`Category::ALL`], sem as vazias.
+pub fn category_counts(risks: &[CommitRisk]) -> Vec<(Category, usize)> {
+    Category::ALL
+        .into_iter()
+        .map(|category| (category, risks.iter().filter(|r| r.categories.contains(&category)).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::commit;
+
+    fn structured(risk_level: RiskLevel, categories: &[Category], breaking_change: bool) -> Analysis {
+        Analysis {
+            purpose: "p".to_string(),
+            impact: String::new(),
+            observations: String::new(),
+            risk_level,
+            categories: categories.to_vec(),
+            components: Vec::new(),
+            breaking_change,
+        }
+    }
+
+    #[test]
+    fn classifies_files_by_the_first_matching_list() {
+        let cases = [
+            ("src/main.rs", FileKind::Code),
+            ("tests/api.rs", FileKind::Test),
+            ("pkg/server_test.go", FileKind::Test),
+            ("src/test/resources/app.yml", FileKind::Test),
+            ("Cargo.toml", FileKind::Dependency),
+            ("web/package-lock.json", FileKind::Dependency),
+            ("db/migrate/001_users.rb", FileKind::Schema),
+            ("api/user.proto", FileKind::Schema),
+            ("config/app.yml", FileKind::Config),
+            ("Dockerfile", FileKind::Config),
+            (".env.local", FileKind::Config),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(file_kind(path), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn reads_categories_from_prefix_or_keywords() {
+        let cases: [(&str, &[Category]); 9] = [
+            ("feat(api): rota de usuarios", &[Category::Feature]),
+            ("fix!: remove campo antigo", &[Category::Bugfix]),
+            ("chore(deps): atualiza serde", &[Category::Dependency]),
+            ("refactor: corrige e adiciona", &[Category::Refactor]),
+            ("Corrige bug e adiciona validação", &[Category::Bugfix, Category::Feature]),
+            ("Renamed the parser module", &[Category::Refactor]),
+            // palavras que so comecam com a palavra-chave nao contam
+            ("Add fixture for the parser", &[Category::Feature]),
+            ("Update fixtures and debugger config", &[]),
+            ("wip", &[]),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(message_categories(message), expected, "{message}");
+        }
+    }
+
+    #[test]
+    fn scores_size_spread_sensitive_files_and_model() {
+        // (commit, texto, analise estruturada, pontuacao, nivel, categorias, quebra)
+        let cases = [
+            // 0 linhas, 1 arquivo: so o espalhamento
+            (commit("wip", &["src/lib.rs"], 0), "", None, 2, None, vec![], false),
+            // 7 linhas = 9, 3 arquivos = 6, schema + dependencia + config = 30, ALTO = 30
+            (
+                commit("fix: corrige migração", &["migrations/001.sql", "Cargo.toml", "config/app.yml"], 7),
+                "**RISCO**: ALTO, mexe no banco",
+                None,
+                75,
+                Some(RiskLevel::High),
+                vec![Category::Bugfix, Category::Dependency, Category::Config, Category::Schema],
+                false,
+            ),
+            // so testes: (30 + 2 + 30) / 3, e a categoria da mensagem some
+            (
+                commit("feat: adiciona testes", &["tests/api.rs"], 1023),
+                "RISK: HIGH",
+                None,
+                20,
+                Some(RiskLevel::High),
+                vec![Category::TestOnly],
+                false,
+            ),
+            // a analise estruturada vence o texto: 2 + MEDIO 15 + quebra 15
+            (
+                commit("wip", &["src/lib.rs"], 0),
+                "RISCO: ALTO",
+                Some(structured(RiskLevel::Medium, &[Category::Refactor], true)),
+                32,
+                Some(RiskLevel::Medium),
+                vec![Category::Refactor],
+                true,
+            ),
+            // 30 + 15 + 15 + 30 + 15 passa do teto
+            (
+                commit("wip", &["a.sql", "b.sql", "c.sql", "d.sql", "e.sql", "f.sql", "g.sql", "h.sql"], 100_000),
+                "",
+                Some(structured(RiskLevel::High, &[], true)),
+                MAX_SCORE,
+                Some(RiskLevel::High),
+                vec![Category::Schema],
+                true,
+            ),
+        ];
+        for (commit, text, analysis, score, level, categories, breaking_change) in cases {
+            let risk = CommitRisk::assess(&commit, text, analysis.as_ref());
+            assert_eq!(risk.score, score, "{}", commit.message);
+            assert_eq!(risk.level, level, "{}", commit.message);
+            assert_eq!(risk.categories, categories, "{}", commit.message);
+            assert_eq!(risk.breaking_change, breaking_change, "{}", commit.message);
+        }
+    }
+
+    #[test]
+    fn keeps_only_the_first_line_of_the_message() {
+        let risk = CommitRisk::assess(&commit("titulo\n\ncorpo", &["src/lib.rs"], 1), "", None);
+        assert_eq!(risk.short_hash, "abcdef1");
+        assert_eq!(risk.message, "titulo");
+    }
+
+    #[test]
+    fn ranks_by_score_keeping_ties_in_order() {
+        let mut risks: Vec<CommitRisk> = [("a", 10), ("b", 40), ("c", 10), ("d", 70)]
+            .into_iter()
+            .map(|(hash, score)| CommitRisk {
+                short_hash: hash.to_string(),
+                message: String::new(),
+                score,
+                level: None,
+                categories: Vec::new(),
+                breaking_change: false,
+            })
+            .collect();
+        rank(&mut risks);
+        let order: Vec<&str> = risks.iter().map(|r| r.short_hash.as_str()).collect();
+        assert_eq!(order, ["d", "b", "a", "c"]);
+    }
+}
@@ -0,0 +1,150 @@
+//! Templates de texto com `{{variavel}}` e blocos `{{#if variavel}}...{{else}}...{{/if}}`.
+//!
+//! Uma variável vazia é falsa no `#if`. Variáveis fora da lista aceita são erro ao ler o
+//! template, não ao montar o prompt.
+
+use crate::i18n::Messages;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Var(String),
+    If { var: String, then: Vec<Node>, otherwise: Vec<Node> },
+}
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+// bloco #if ainda aberto durante a leitura
+struct OpenIf {
+    var: String,
+    /// O que vinha antes do bloco.
+    outer: Vec<Node>,
+    /// Preenchido quando aparece o `{{else}}`.
+    then: Option<Vec<Node>>,
+}
+
+impl Template {
+    pub fn parse(source: &str, known: &[&str], m: &Messages) -> Result<Self, String> {
+        let check = |name: &str| {
+            if known.contains(&name) {
+                Ok(name.to_string())
+            } else {
+                Err((m.template_unknown_variable)(&name, &known.join(", ")))
+            }
+        };
+
+        let mut stack: Vec<OpenIf> = Vec::new();
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut rest = source;
+
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                nodes.push(Node::Text(rest[..start].to_string()));
+            }
+            let after = &rest[start + 2..];
+            let end = after.find("}}").ok_or(m.template_unclosed_tag)?;
+            let tag = after[..end].trim();
+            rest = &after[end + 2..];
+
+            if let Some(var) = tag.strip_prefix("#if ") {
+                stack.push(OpenIf { var: check(var.trim())?, outer: std::mem::take(&mut nodes), then: None });
+            } else if tag == "else" {
+                let open = stack.last_mut().filter(|open| open.then.is_none()).ok_or(m.template_stray_else)?;
+                open.then = Some(std::mem::take(&mut nodes));
+            } else if tag == "/if" {
+                let open = stack.pop().ok_or(m.template_stray_end)?;
+                let branch = std::mem::replace(&mut nodes, open.outer);
+                let (then, otherwise) = match open.then {
+                    Some(then) => (then, branch),
+                    None => (branch, Vec::new()),
+                };
+                nodes.push(Node::If { var: open.var, then, otherwise });
+            } else {
+                nodes.push(Node::Var(check(tag)?));
+            }
+        }
+        if !rest.is_empty() {
+            nodes.push(Node::Text(rest.to_string()));
+        }
+
+        if let Some(open) = stack.last() {
+            return Err((m.template_unclosed_if)(&open.var));
+        }
+        Ok(Template { nodes })
+    }
+
+    pub fn render(&self, vars: &HashMap<&str, String>) -> String {
+        let mut output = String::new();
+        render_nodes(&self.nodes, vars, &mut output);
+        output
+    }
+}
+
+fn render_nodes(nodes: &[Node], vars: &HashMap<&str, String>, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Var(name) => output.push_str(vars.get(name.as_str()).map_or("", String::as_str)),
+            Node::If { var, then, otherwise } => {
+                let set = vars.get(var.as_str()).is_some_and(|value| !value.is_empty());
+                render_nodes(if set { then } else { otherwise }, vars, output);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Locale;
+
+    const KNOWN: &[&str] = &["role", "focus", "pending"];
+
+    fn render(source: &str, vars: &[(&'static str, &str)]) -> String {
+        let vars: HashMap<&str, String> = vars.iter().map(|(k, v)| (*k, v.to_string())).collect();
+        Template::parse(source, KNOWN, Locale::PtBr.messages()).unwrap().render(&vars)
+    }
+
+    #[test]
+    fn renders_variables_and_branches() {
+        let source = "{{role}}: {{#if focus}}foco em {{focus}}{{else}}sem foco{{/if}}.";
+        let cases = [
+            (vec![("role", "revisor"), ("focus", "testes")], "revisor: foco em testes."),
+            (vec![("role", "revisor"), ("focus", "")], "revisor: sem foco."),
+            (vec![], ": sem foco."),
+        ];
+        for (vars, expected) in cases {
+            assert_eq!(render(source, &vars), expected);
+        }
+    }
+
+    #[test]
+    fn nested_ifs_and_text_without_tags() {
+        let source = "{{#if role}}[{{#if pending}}antes{{/if}}]{{/if}}";
+        assert_eq!(render(source, &[("role", "x"), ("pending", "sim")]), "[antes]");
+        assert_eq!(render(source, &[("role", "x")]), "[]");
+        assert_eq!(render(source, &[("pending", "sim")]), "");
+        assert_eq!(render("só texto, com { e }", &[]), "só texto, com { e }");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let unknown = |name: &str| format!("variável desconhecida `{}` (use role, focus, pending)", name);
+        let cases = [
+            ("{{role", "`{{` sem o `}}` correspondente".to_string()),
+            ("{{else}}", "`{{else}}` fora de um `{{#if}}`".to_string()),
+            ("{{#if role}}a{{else}}b{{else}}c{{/if}}", "`{{else}}` fora de um `{{#if}}`".to_string()),
+            ("a{{/if}}", "`{{/if}}` sem `{{#if}}`".to_string()),
+            ("{{#if role}}a{{#if focus}}b{{/if}}", "`{{#if role}}` sem `{{/if}}`".to_string()),
+            ("{{nome}}", unknown("nome")),
+            ("{{#if nome}}a{{/if}}", unknown("nome")),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(Template::parse(source, KNOWN, Locale::PtBr.messages()).unwrap_err(), expected, "{source}");
+        }
+    }
+}
@@ -0,0 +1,19 @@
+// Códigos de cores ANSI
+pub const COLOR_RESET: &str = "\x1b[0m";
+pub const COLOR_CYAN: &str = "\x1b[1;36m";
+pub const COLOR_BLUE: &str = "\x1b[1;34m";
+pub const COLOR_YELLOW: &str = "\x1b[1;33m";
+pub const COLOR_GREEN: &str = "\x1b[1;32m";
+pub const COLOR_RED: &str = "\x1b[1;31m";
+pub const COLOR_WHITE: &str = "\x1b[1;37m";
+pub const COLOR_MAGENTA: &str = "\x1b[1;35m";
+pub const COLOR_GRAY: &str = "\x1b[1;90m";
+
+// Etiquetas de log com nomes próprios; as outras mudam com o idioma e ficam em `i18n`
+pub const LABEL_OLLAMA: &str = "OLLAMA";
+pub const LABEL_OPENAI: &str = "OPENAI";
+pub const LABEL_ECHO: &str = "ECHO";
+pub const LABEL_JSON: &str = "JSON";
+
+// Separador
+pub const SEPARATOR: &str = "────────────────────────────────────────────────────────────";
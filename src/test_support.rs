@@ -0,0 +1,80 @@
+//! Dados de teste usados por mais de um módulo.
+
+use crate::git::CommitInfo;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub fn commit(message: &str, files: &[&str], insertions: u32) -> CommitInfo {
+    CommitInfo {
+        hash: "abcdef1234567890".to_string(),
+        short_hash: "abcdef1".to_string(),
+        message: message.to_string(),
+        body: String::new(),
+        author: "Fulano".to_string(),
+        date: "2024-01-01".to_string(),
+        files_changed: files.iter().map(|f| f.to_string()).collect(),
+        insertions,
+        deletions: 0,
+    }
+}
+
+/// Diff de um arquivo novo com `lines` linhas.
+pub fn new_file_diff(path: &str, lines: usize) -> String {
+    let mut diff = format!(
+        "diff --git a/{path} b/{path}\nnew file mode 100644\n--- /dev/null\n+++ b/{path}\n@@ -0,0 +1,{lines} @@\n"
+    );
+    for i in 0..lines {
+        writeln!(diff, "+let valor_{} = {};", i, i).unwrap();
+    }
+    diff
+}
+
+/// Diretório temporário, apagado quando sai de escopo.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let unique = NEXT.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("git-diff-extractor-{}-{}", std::process::id(), unique));
+        std::fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Roda o git em `dir`, com autor fixo e sem ler as configurações de quem roda os testes.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(["-c", "user.name=Fulano", "-c", "user.email=fulano@exemplo.com", "-c", "commit.gpgsign=false"])
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Repositório com um commit que adiciona `src/lib.rs`.
+pub fn temp_repo() -> TempDir {
+    let dir = TempDir::new();
+    git(dir.path(), &["init", "-q"]);
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src/lib.rs"), "pub fn soma(a: i32, b: i32) -> i32 {\n    a + b\n}\n").unwrap();
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "adiciona soma"]);
+    dir
+}
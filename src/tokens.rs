@@ -0,0 +1,29 @@
+//! Estimativa de tokens sem depender do tokenizer de cada modelo.
+
+// codigo tem muitos simbolos e identificadores quebrados; 3 caracteres por token erra pra mais
+const CHARS_PER_TOKEN: usize = 3;
+
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Janela de contexto do modelo e quanto dela fica reservado para a resposta.
+#[derive(Debug, Clone, Copy)]
+pub struct TokenBudget {
+    pub context_window: usize,
+    pub answer_tokens: usize,
+}
+
+impl TokenBudget {
+    /// Tokens que sobram para o conteúdo depois do prompt (sem o conteúdo) e da resposta.
+    pub fn available_for(&self, empty_prompt: &str) -> usize {
+        self.context_window
+            .saturating_sub(self.answer_tokens)
+            .saturating_sub(estimate_tokens(empty_prompt))
+    }
+
+    /// Se o prompt inteiro cabe na janela junto com a resposta.
+    pub fn fits(&self, prompt: &str) -> bool {
+        estimate_tokens(prompt) + self.answer_tokens <= self.context_window
+    }
+}